@@ -5,6 +5,48 @@
 // #![doc(html_logo_url = "assets/icon.png", html_favicon_url = "assets/icon.png")]
 #![no_std] // tells the compiler "don't automatically link std"
 
+pub use obel_app as app;
 pub use obel_diagnostic as diagnostic;
 pub use obel_platform as platform;
 pub use obel_reflect as reflect;
+
+/// The engine version, which dynamic plugins must share with the app loading them.
+#[cfg(feature = "dynamic_plugin")]
+pub const VERSION: &core::ffi::CStr =
+    match core::ffi::CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes())
+    {
+        Ok(version) => version,
+        Err(_) => panic!("version contains a NUL byte"),
+    };
+
+/// Creates a [`DynamicPluginLoader`](app::DynamicPluginLoader) accepting plugins built against
+/// this engine [`VERSION`].
+#[cfg(feature = "dynamic_plugin")]
+pub fn dynamic_plugin_loader() -> app::DynamicPluginLoader {
+    app::DynamicPluginLoader::new(VERSION)
+}
+
+/// Exports a [`Plugin`](app::Plugin) from a `dylib` crate, to be loaded by a
+/// [`DynamicPluginLoader`](app::DynamicPluginLoader).
+///
+/// The crate must enable the `dynamic_linking` feature, like the app loading it, so that both
+/// share the same engine.
+///
+/// ```ignore
+/// struct GameplayPlugin;
+///
+/// impl Plugin for GameplayPlugin {
+///     fn build(&self, app: &mut App) {
+///         // ...
+///     }
+/// }
+///
+/// obel::dynamic_plugin!(GameplayPlugin);
+/// ```
+#[cfg(feature = "dynamic_plugin")]
+#[macro_export]
+macro_rules! dynamic_plugin {
+    ($plugin:expr) => {
+        $crate::app::dynamic_plugin!($plugin, $crate::VERSION);
+    };
+}
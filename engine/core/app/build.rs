@@ -0,0 +1,23 @@
+//! Records the compiler version, which dynamic plugins must share with the app loading them.
+
+use std::{env, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    // only dynamic plugins need the compiler version
+    if env::var_os("CARGO_FEATURE_DYNAMIC_PLUGIN").is_none() {
+        return;
+    }
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_owned())
+        .unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=OBEL_RUSTC_VERSION={version}");
+}
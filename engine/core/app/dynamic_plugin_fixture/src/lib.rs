@@ -0,0 +1,82 @@
+//! A dynamic plugin library loaded by the `DynamicPluginLoader` tests.
+//!
+//! The plugin records the lifecycle calls it receives in counters, which the tests read
+//! through [`fixture_counts`].
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use obel::app::{App, Plugin};
+
+static BUILDS: AtomicU32 = AtomicU32::new(0);
+static FINISHES: AtomicU32 = AtomicU32::new(0);
+static CLEANUPS: AtomicU32 = AtomicU32::new(0);
+static DROPS: AtomicU32 = AtomicU32::new(0);
+static READY: AtomicBool = AtomicBool::new(true);
+
+/// The name of the plugin [`FixturePlugin`] depends on.
+pub const FIXTURE_DEPENDENCY: &str = "obel_app_dynamic_plugin_fixture::dependency";
+
+/// The number of lifecycle calls received by [`FixturePlugin`]s.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixtureCounts {
+    /// Calls to [`Plugin::build`].
+    pub builds: u32,
+    /// Calls to [`Plugin::finish`].
+    pub finishes: u32,
+    /// Calls to [`Plugin::cleanup`].
+    pub cleanups: u32,
+    /// Plugins dropped.
+    pub drops: u32,
+}
+
+/// Returns the lifecycle calls received so far.
+#[expect(unsafe_code, reason = "Exported symbols must not be mangled.")]
+#[unsafe(no_mangle)]
+pub extern "C" fn fixture_counts() -> FixtureCounts {
+    FixtureCounts {
+        builds: BUILDS.load(Ordering::SeqCst),
+        finishes: FINISHES.load(Ordering::SeqCst),
+        cleanups: CLEANUPS.load(Ordering::SeqCst),
+        drops: DROPS.load(Ordering::SeqCst),
+    }
+}
+
+/// Sets the value returned by [`Plugin::ready`].
+#[expect(unsafe_code, reason = "Exported symbols must not be mangled.")]
+#[unsafe(no_mangle)]
+pub extern "C" fn fixture_set_ready(ready: bool) {
+    READY.store(ready, Ordering::SeqCst);
+}
+
+/// The plugin exported by this library.
+pub struct FixturePlugin;
+
+impl Plugin for FixturePlugin {
+    fn build(&self, _app: &mut App) {
+        BUILDS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn ready(&self, _app: &App) -> bool {
+        READY.load(Ordering::SeqCst)
+    }
+
+    fn finish(&self, _app: &mut App) {
+        FINISHES.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn cleanup(&self, _app: &mut App) {
+        CLEANUPS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn dependencies(&self) -> Vec<&str> {
+        vec![FIXTURE_DEPENDENCY]
+    }
+}
+
+impl Drop for FixturePlugin {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+obel::dynamic_plugin!(FixturePlugin);
@@ -0,0 +1,734 @@
+use crate::{PlaceholderPlugin, Plugin, Plugins, PluginsState};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{mem, num::NonZero};
+use obel_platform::{
+    collections::HashSet,
+    sync::{Arc, Mutex, PoisonError},
+};
+use thiserror::Error;
+
+/// An error produced while configuring an [`App`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AppError {
+    /// A unique plugin was added twice.
+    #[error("duplicate plugin {plugin_name:?}")]
+    DuplicatePlugin {
+        /// The [`Plugin::name`] of the duplicated plugin.
+        plugin_name: String,
+    },
+    /// A plugin depends on a plugin that was never added.
+    #[error("plugin {plugin_name:?} depends on {dependency:?}, which was not added")]
+    MissingPluginDependency {
+        /// The [`Plugin::name`] of the plugin declaring the dependency.
+        plugin_name: String,
+        /// The name of the missing dependency.
+        dependency: String,
+    },
+}
+
+/// The type of function called by [`App::run`].
+pub(crate) type RunnerFn = Box<dyn FnOnce(App) -> AppExit>;
+
+/// [`App`] is the primary API for writing user applications. It automates the setup of a
+/// standard lifecycle and provides interface glue for [plugins](`Plugin`).
+///
+/// Game and server binaries usually compose the same gameplay [`Plugin`]s and
+/// [`PluginGroup`](crate::PluginGroup)s differently:
+///
+/// ```
+/// # use obel_app::prelude::*;
+/// fn gameplay_plugin(app: &mut App) {
+///     // ...
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugins(gameplay_plugin);
+/// app.run();
+/// ```
+pub struct App {
+    plugin_registry: Vec<Box<dyn Plugin>>,
+    plugin_names: HashSet<String>,
+    plugin_build_depth: usize,
+    plugins_state: PluginsState,
+    /// The function that will manage the app's lifecycle.
+    ///
+    /// Obel provides the [`ScheduleRunnerPlugin`](crate::ScheduleRunnerPlugin) for tests and
+    /// headless servers; the default runner is [`run_once`].
+    runner: RunnerFn,
+    exit_signal: AppExitSignal,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    /// Creates a new [`App`] without any plugins.
+    pub fn new() -> App {
+        App {
+            plugin_registry: Vec::default(),
+            plugin_names: HashSet::default(),
+            plugin_build_depth: 0,
+            plugins_state: PluginsState::Adding,
+            runner: Box::new(run_once),
+            exit_signal: AppExitSignal::default(),
+        }
+    }
+
+    /// Runs a single frame of the app.
+    ///
+    /// This ticks the local executors of the global task pools (see
+    /// [`TaskPoolPlugin`](crate::TaskPoolPlugin)), so it should be called from the thread
+    /// that owns the app, usually the main thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called while a plugin is being built.
+    pub fn update(&mut self) {
+        if self.is_building_plugins() {
+            panic!("App::update() was called while a plugin was building.");
+        }
+
+        crate::task_pool_plugin::tick_global_task_pools();
+    }
+
+    /// Runs the [`App`] by calling its [runner](Self::set_runner).
+    ///
+    /// This will (re)build the [`App`] first. For general usage, see the example on the item
+    /// level documentation.
+    ///
+    /// # Caveats
+    ///
+    /// Calls to [`App::run()`] will never return on iOS and Web.
+    ///
+    /// Headless apps can generally expect this method to return control to the caller when
+    /// it completes, but that is not the case for windowed apps. Windowed apps are typically
+    /// driven by an event loop and some platforms expect the program to terminate when the
+    /// event loop ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not all plugins have been built.
+    pub fn run(&mut self) -> AppExit {
+        if self.is_building_plugins() {
+            panic!("App::run() was called while a plugin was building.");
+        }
+
+        let runner = mem::replace(&mut self.runner, Box::new(run_once));
+        let app = mem::take(self);
+        (runner)(app)
+    }
+
+    /// Sets the function that will be called when the app is run.
+    ///
+    /// The runner function `f` is called only once by [`App::run`]. If the
+    /// presence of a main loop in the app is desired, it is the responsibility of the runner
+    /// function to provide it.
+    ///
+    /// The runner function is usually not set manually, but by Obel integrated plugins
+    /// (e.g. [`ScheduleRunnerPlugin`](crate::ScheduleRunnerPlugin)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use obel_app::prelude::*;
+    /// fn my_runner(mut app: App) -> AppExit {
+    ///     loop {
+    ///         println!("In main loop");
+    ///         app.update();
+    ///         if let Some(exit) = app.should_exit() {
+    ///             return exit;
+    ///         }
+    ///         # app.exit();
+    ///     }
+    /// }
+    ///
+    /// App::new()
+    ///     .set_runner(my_runner);
+    /// ```
+    pub fn set_runner(&mut self, f: impl FnOnce(App) -> AppExit + 'static) -> &mut Self {
+        self.runner = Box::new(f);
+        self
+    }
+
+    /// Requests the app to exit with [`AppExit::Success`] at the end of the current frame.
+    pub fn exit(&mut self) {
+        self.exit_with(AppExit::Success);
+    }
+
+    /// Requests the app to exit with the given [`AppExit`] at the end of the current frame.
+    ///
+    /// See [`AppExitSignal::exit`] for how several requests are combined.
+    pub fn exit_with(&mut self, exit: AppExit) {
+        self.exit_signal.exit(exit);
+    }
+
+    /// Returns a handle that can request this app to exit from any thread.
+    ///
+    /// This is how headless servers shut down gracefully, e.g. from a signal handler.
+    pub fn exit_signal(&self) -> AppExitSignal {
+        self.exit_signal.clone()
+    }
+
+    /// Checks if an exit was requested, through [`App::exit`] or an [`AppExitSignal`].
+    ///
+    /// Runners call this after each update to decide whether to keep looping.
+    pub fn should_exit(&self) -> Option<AppExit> {
+        self.exit_signal.requested()
+    }
+
+    /// Returns the state of all plugins. This is usually called by the event loop, but can be
+    /// useful for situations where you want to use [`App::update`].
+    #[inline]
+    pub fn plugins_state(&self) -> PluginsState {
+        let mut overall_plugins_state = match self.plugins_state {
+            PluginsState::Adding => {
+                if self.plugin_registry.iter().all(|plugin| plugin.ready(self)) {
+                    PluginsState::Ready
+                } else {
+                    PluginsState::Adding
+                }
+            }
+            state => state,
+        };
+
+        // If any plugins are still being built, the overall state is `Adding`.
+        if self.plugin_build_depth > 0 {
+            overall_plugins_state = PluginsState::Adding;
+        }
+
+        overall_plugins_state
+    }
+
+    /// Runs [`Plugin::finish`] for each plugin. This is usually called by the event loop once all
+    /// plugins are ready, but can be useful for situations where you want to use [`App::update`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called while a plugin is being built, or if a plugin depends on a plugin that
+    /// was never added, see [`App::check_plugin_dependencies`].
+    #[track_caller]
+    pub fn finish(&mut self) {
+        if self.is_building_plugins() {
+            panic!("App::finish() was called while a plugin was building.");
+        }
+
+        if let Err(error) = self.check_plugin_dependencies() {
+            panic!("Error finishing app: {error}");
+        }
+
+        let plugins = mem::take(&mut self.plugin_registry);
+        for plugin in &plugins {
+            plugin.finish(self);
+        }
+        self.plugin_registry = plugins;
+        self.plugins_state = PluginsState::Finished;
+    }
+
+    /// Runs [`Plugin::cleanup`] for each plugin. This is usually called by the event loop after
+    /// [`App::finish`], but can be useful for situations where you want to use [`App::update`].
+    pub fn cleanup(&mut self) {
+        let plugins = mem::take(&mut self.plugin_registry);
+        for plugin in &plugins {
+            plugin.cleanup(self);
+        }
+        self.plugin_registry = plugins;
+        self.plugins_state = PluginsState::Cleaned;
+    }
+
+    /// Returns `true` if a plugin is currently being built.
+    pub(crate) fn is_building_plugins(&self) -> bool {
+        self.plugin_build_depth > 0
+    }
+
+    pub(crate) fn add_boxed_plugin(
+        &mut self,
+        plugin: Box<dyn Plugin>,
+    ) -> Result<&mut Self, AppError> {
+        log::debug!("added plugin: {}", plugin.name());
+        if plugin.is_unique() && self.plugin_names.contains(plugin.name()) {
+            Err(AppError::DuplicatePlugin {
+                plugin_name: plugin.name().to_string(),
+            })?;
+        }
+
+        // Reserve position in the plugin registry. If the plugin adds more plugins,
+        // they'll all end up in insertion order.
+        let index = self.plugin_registry.len();
+        self.plugin_registry.push(Box::new(PlaceholderPlugin));
+
+        self.plugin_build_depth += 1;
+
+        let f = AssertUnwindSafe(|| plugin.build(self));
+
+        #[cfg(feature = "std")]
+        let result = catch_unwind(f);
+
+        #[cfg(not(feature = "std"))]
+        f();
+
+        self.plugin_names.insert(plugin.name().to_string());
+        self.plugin_build_depth -= 1;
+
+        #[cfg(feature = "std")]
+        if let Err(payload) = result {
+            resume_unwind(payload);
+        }
+
+        self.plugin_registry[index] = plugin;
+        Ok(self)
+    }
+
+    /// Removes the added plugins matching `predicate`, forgetting their names once no plugin
+    /// with the same name is left.
+    #[cfg(feature = "dynamic_plugin")]
+    pub(crate) fn remove_plugins(&mut self, mut predicate: impl FnMut(&dyn Plugin) -> bool) {
+        let mut removed = Vec::new();
+        self.plugin_registry.retain(|plugin| {
+            let remove = predicate(plugin.as_ref());
+            if remove {
+                removed.push(plugin.name().to_string());
+            }
+            !remove
+        });
+        for name in removed {
+            if !self.plugin_registry.iter().any(|plugin| plugin.name() == name) {
+                self.plugin_names.remove(&name);
+            }
+        }
+    }
+
+    /// Returns `true` if the [`Plugin`] has already been added.
+    pub fn is_plugin_added<T>(&self) -> bool
+    where
+        T: Plugin,
+    {
+        self.plugin_registry.iter().any(|p| p.downcast_ref::<T>().is_some())
+    }
+
+    /// Returns `true` if a [`Plugin`] with the given [`Plugin::name`] has already been added.
+    pub fn is_plugin_name_added(&self, name: &str) -> bool {
+        self.plugin_names.contains(name)
+    }
+
+    /// Returns a vector of references to all plugins of type `T` that have been added.
+    ///
+    /// This can be used to read the settings of any existing plugins.
+    /// This vector will be empty if no plugins of that type have been added.
+    /// If multiple copies of the same plugin are added to the [`App`], they will be listed in insertion order in this vector.
+    ///
+    /// ```
+    /// # use obel_app::prelude::*;
+    /// # let mut app = App::new();
+    /// # #[derive(Default)]
+    /// # struct ImagePlugin {
+    /// #    default_sampler: bool,
+    /// # }
+    /// # impl Plugin for ImagePlugin {
+    /// #    fn build(&self, app: &mut App) {}
+    /// # }
+    /// # app.add_plugins(ImagePlugin::default());
+    /// let default_sampler = app.get_added_plugins::<ImagePlugin>()[0].default_sampler;
+    /// ```
+    pub fn get_added_plugins<T>(&self) -> Vec<&T>
+    where
+        T: Plugin,
+    {
+        self.plugin_registry.iter().filter_map(|p| p.downcast_ref()).collect()
+    }
+
+    /// Checks that every [`Plugin::dependencies`] entry of every added plugin names a plugin
+    /// that has also been added.
+    ///
+    /// This is called by [`App::finish`], but can be called earlier to report errors without
+    /// panicking. The first missing dependency found is returned.
+    pub fn check_plugin_dependencies(&self) -> Result<(), AppError> {
+        for plugin in &self.plugin_registry {
+            for dependency in plugin.dependencies() {
+                if !self.plugin_names.contains(dependency) {
+                    return Err(AppError::MissingPluginDependency {
+                        plugin_name: plugin.name().to_string(),
+                        dependency: dependency.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Installs a [`Plugin`] collection.
+    ///
+    /// Obel prioritizes modularity as a core principle. **All** engine features are implemented
+    /// as plugins, even the complex ones like rendering.
+    ///
+    /// [`Plugin`]s can be grouped into a set by using a [`PluginGroup`].
+    ///
+    /// To customize the plugins in the group (reorder, disable a plugin, add a new plugin
+    /// before / after another plugin), call [`build()`](super::PluginGroup::build) on the group,
+    /// which will convert it to a [`PluginGroupBuilder`](crate::PluginGroupBuilder).
+    ///
+    /// You can also specify a group of [`Plugin`]s by using a tuple over [`Plugin`]s and
+    /// [`PluginGroup`]s. See [`Plugins`] for more details.
+    ///
+    /// ## Examples
+    /// ```
+    /// # use obel_app::{prelude::*, PluginGroupBuilder, NoopPluginGroup as MinimalPlugins};
+    /// #
+    /// # pub struct LogPlugin;
+    /// # impl Plugin for LogPlugin {
+    /// #     fn build(&self, app: &mut App) {}
+    /// # }
+    /// App::new()
+    ///     .add_plugins(MinimalPlugins);
+    /// App::new()
+    ///     .add_plugins((MinimalPlugins, LogPlugin));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the plugins had already been added to the application.
+    ///
+    /// [`PluginGroup`]:super::PluginGroup
+    #[track_caller]
+    pub fn add_plugins<M>(&mut self, plugins: impl Plugins<M>) -> &mut Self {
+        if matches!(self.plugins_state(), PluginsState::Cleaned | PluginsState::Finished) {
+            panic!(
+                "Plugins cannot be added after App::cleanup() or App::finish() has been called."
+            );
+        }
+        plugins.add_to_app(self);
+        self
+    }
+}
+
+/// Runs the app once: waits for all plugins to be ready, finishes and cleans them up,
+/// then runs a single [`App::update`].
+///
+/// This is the default runner of an [`App`].
+fn run_once(mut app: App) -> AppExit {
+    while app.plugins_state() == PluginsState::Adding {
+        crate::task_pool_plugin::tick_global_task_pools();
+    }
+    app.finish();
+    app.cleanup();
+
+    app.update();
+
+    app.should_exit().unwrap_or(AppExit::Success)
+}
+
+/// The status code returned by [`App::run`], requested through [`App::exit`],
+/// [`App::exit_with`] or an [`AppExitSignal`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AppExit {
+    /// [`App`] exited without any problems.
+    #[default]
+    Success,
+    /// The [`App`] experienced an unhandleable error.
+    /// Holds the exit code we expect our app to return.
+    Error(NonZero<u8>),
+}
+
+impl AppExit {
+    /// Creates a [`AppExit::Error`] with an error code of 1.
+    #[must_use]
+    pub const fn error() -> Self {
+        Self::Error(NonZero::<u8>::MIN)
+    }
+
+    /// Returns `true` if `self` is a [`AppExit::Success`].
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        matches!(self, AppExit::Success)
+    }
+
+    /// Returns `true` if `self` is a [`AppExit::Error`].
+    #[must_use]
+    pub const fn is_error(&self) -> bool {
+        matches!(self, AppExit::Error(_))
+    }
+
+    /// Creates a [`AppExit`] from a code.
+    ///
+    /// When `code` is 0 a [`AppExit::Success`] is constructed otherwise a
+    /// [`AppExit::Error`] is constructed.
+    #[must_use]
+    pub const fn from_code(code: u8) -> Self {
+        match NonZero::<u8>::new(code) {
+            Some(code) => Self::Error(code),
+            None => Self::Success,
+        }
+    }
+}
+
+impl From<u8> for AppExit {
+    fn from(value: u8) -> Self {
+        Self::from_code(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::process::Termination for AppExit {
+    fn report(self) -> std::process::ExitCode {
+        match self {
+            AppExit::Success => std::process::ExitCode::SUCCESS,
+            // We leave logging an error to our users
+            AppExit::Error(value) => std::process::ExitCode::from(value.get()),
+        }
+    }
+}
+
+/// A cloneable, thread-safe handle used to request an [`App`] to exit.
+///
+/// Obtained from [`App::exit_signal`]. Runners check it after every update through
+/// [`App::should_exit`], so the frame in progress always completes before the app exits.
+#[derive(Debug, Clone, Default)]
+pub struct AppExitSignal(Arc<Mutex<Option<AppExit>>>);
+
+impl AppExitSignal {
+    /// Requests the app to exit.
+    ///
+    /// If several exits are requested before the runner observes them, the first
+    /// [`AppExit::Error`] wins over any [`AppExit::Success`].
+    pub fn exit(&self, exit: AppExit) {
+        let mut requested = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match *requested {
+            Some(AppExit::Error(_)) => {}
+            _ => *requested = Some(exit),
+        }
+    }
+
+    /// Returns the exit requested so far, if any.
+    pub fn requested(&self) -> Option<AppExit> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+#[cfg(feature = "std")]
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+
+#[cfg(not(feature = "std"))]
+use core::panic::AssertUnwindSafe;
+
+#[cfg(test)]
+mod tests {
+    use super::AppError;
+    use crate::{App, AppExit, Plugin, PluginsState, plugin_name};
+    use alloc::{string::ToString, sync::Arc, vec, vec::Vec};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    struct PluginA;
+    impl Plugin for PluginA {
+        fn build(&self, _app: &mut App) {}
+    }
+    struct PluginB;
+    impl Plugin for PluginB {
+        fn build(&self, _app: &mut App) {}
+    }
+    struct PluginC<T>(T);
+    impl<T: Send + Sync + 'static> Plugin for PluginC<T> {
+        fn build(&self, _app: &mut App) {}
+    }
+    struct PluginD;
+    impl Plugin for PluginD {
+        fn build(&self, _app: &mut App) {}
+        fn is_unique(&self) -> bool {
+            false
+        }
+    }
+
+    struct PluginE(Arc<AtomicUsize>);
+
+    impl Plugin for PluginE {
+        fn build(&self, _app: &mut App) {}
+
+        fn finish(&self, _app: &mut App) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn cleanup(&self, _app: &mut App) {
+            self.0.fetch_add(10, Ordering::Relaxed);
+        }
+    }
+
+    struct DependsOnA;
+
+    impl Plugin for DependsOnA {
+        fn build(&self, _app: &mut App) {}
+
+        fn dependencies(&self) -> Vec<&str> {
+            vec![plugin_name::<PluginA>()]
+        }
+    }
+
+    #[test]
+    fn can_add_two_plugins() {
+        App::new().add_plugins((PluginA, PluginB));
+    }
+
+    #[test]
+    #[should_panic]
+    fn cant_add_twice_the_same_plugin() {
+        App::new().add_plugins((PluginA, PluginA));
+    }
+
+    #[test]
+    fn can_add_twice_the_same_plugin_with_different_type_param() {
+        App::new().add_plugins((PluginC(0), PluginC(true)));
+    }
+
+    #[test]
+    fn can_add_twice_the_same_plugin_not_unique() {
+        let mut app = App::new();
+        app.add_plugins((PluginD, PluginD));
+        assert_eq!(app.get_added_plugins::<PluginD>().len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "App::finish() was called while a plugin was building.")]
+    fn cant_call_app_finish_from_plugin_build() {
+        struct PluginRun;
+        struct InnerPlugin;
+        impl Plugin for InnerPlugin {
+            fn build(&self, _: &mut App) {}
+        }
+        impl Plugin for PluginRun {
+            fn build(&self, app: &mut App) {
+                app.add_plugins(InnerPlugin).finish();
+            }
+        }
+        App::new().add_plugins(PluginRun);
+    }
+
+    #[test]
+    fn plugins_added_by_plugins_keep_insertion_order() {
+        fn outer(app: &mut App) {
+            app.add_plugins(PluginB);
+        }
+
+        let mut app = App::new();
+        app.add_plugins((PluginA, outer));
+        assert!(app.is_plugin_added::<PluginA>());
+        assert!(app.is_plugin_added::<PluginB>());
+        assert!(app.is_plugin_name_added(plugin_name::<PluginB>()));
+    }
+
+    #[test]
+    fn plugins_state_transitions() {
+        let mut app = App::new();
+        app.add_plugins(PluginA);
+        assert_eq!(app.plugins_state(), PluginsState::Ready);
+        app.finish();
+        assert_eq!(app.plugins_state(), PluginsState::Finished);
+        app.cleanup();
+        assert_eq!(app.plugins_state(), PluginsState::Cleaned);
+    }
+
+    #[test]
+    fn plugin_not_ready() {
+        struct NotReady;
+        impl Plugin for NotReady {
+            fn build(&self, _: &mut App) {}
+            fn ready(&self, _: &App) -> bool {
+                false
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins(NotReady);
+        assert_eq!(app.plugins_state(), PluginsState::Adding);
+    }
+
+    #[test]
+    fn finish_and_cleanup_run_plugin_hooks() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut app = App::new();
+        app.add_plugins(PluginE(calls.clone()));
+        app.finish();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        app.cleanup();
+        assert_eq!(calls.load(Ordering::Relaxed), 11);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Plugins cannot be added after App::cleanup() or App::finish() has been called."
+    )]
+    fn cant_add_plugins_after_finish() {
+        let mut app = App::new();
+        app.finish();
+        app.add_plugins(PluginA);
+    }
+
+    #[test]
+    fn dependencies_can_be_added_in_any_order() {
+        let mut app = App::new();
+        app.add_plugins((DependsOnA, PluginA));
+        assert_eq!(app.check_plugin_dependencies(), Ok(()));
+        app.finish();
+    }
+
+    #[test]
+    fn missing_dependency_is_reported() {
+        let mut app = App::new();
+        app.add_plugins(DependsOnA);
+        assert_eq!(
+            app.check_plugin_dependencies(),
+            Err(AppError::MissingPluginDependency {
+                plugin_name: plugin_name::<DependsOnA>().to_string(),
+                dependency: plugin_name::<PluginA>().to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn run_uses_custom_runner() {
+        let mut app = App::new();
+        app.set_runner(|mut app| {
+            app.update();
+            AppExit::from_code(4)
+        });
+        assert_eq!(app.run(), AppExit::from_code(4));
+        assert!(app.run().is_success());
+    }
+
+    #[test]
+    fn default_runner_finishes_plugins() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut app = App::new();
+        app.add_plugins(PluginE(calls.clone()));
+        assert_eq!(app.run(), AppExit::Success);
+        assert_eq!(calls.load(Ordering::Relaxed), 11);
+    }
+
+    #[test]
+    fn first_error_exit_wins() {
+        let mut app = App::new();
+        assert_eq!(app.should_exit(), None);
+        app.exit();
+        assert_eq!(app.should_exit(), Some(AppExit::Success));
+        app.exit_with(AppExit::error());
+        app.exit_signal().exit(AppExit::from_code(2));
+        app.exit();
+        assert_eq!(app.should_exit(), Some(AppExit::error()));
+    }
+
+    #[test]
+    fn app_exit_codes() {
+        assert!(AppExit::from_code(0).is_success());
+        assert!(AppExit::from(5).is_error());
+        assert_eq!(AppExit::error(), AppExit::from_code(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "which was not added")]
+    fn finish_panics_on_missing_dependency() {
+        App::new().add_plugins(DependsOnA).finish();
+    }
+}
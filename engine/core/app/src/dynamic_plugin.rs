@@ -0,0 +1,511 @@
+#![expect(unsafe_code, reason = "Loading and calling into shared libraries is inherently unsafe.")]
+
+use crate::{App, AppError, Plugin, PluginsState};
+use alloc::{borrow::ToOwned, boxed::Box, string::String, vec::Vec};
+use core::ffi::{CStr, c_char};
+use libloading::{Library, Symbol};
+use obel_platform::sync::{
+    Arc, Weak,
+    atomic::{AtomicBool, Ordering},
+};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The version of the dynamic plugin interface: the exported symbols and
+/// [`DynamicPluginDeclaration`]. Bumped whenever either changes.
+pub const DYNAMIC_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The `rustc --version` the engine was built with. Rust has no stable ABI, so a plugin built
+/// by another compiler cannot be loaded.
+pub const OBEL_RUSTC_VERSION: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("OBEL_RUSTC_VERSION"), "\0").as_bytes()) {
+        Ok(version) => version,
+        Err(_) => panic!("rustc version contains a NUL byte"),
+    };
+
+/// A static whose address identifies this copy of the engine. A plugin sharing the app's
+/// dynamically linked engine reports the same address; a plugin that links its own copy
+/// reports another one.
+static ENGINE: u8 = 0;
+
+/// The symbol returning the [`DynamicPluginDeclaration`] of a library.
+const DECLARATION_SYMBOL: &[u8] = b"_obel_plugin_declaration\0";
+
+/// The symbol creating the [`Plugin`] of a library.
+const CREATE_SYMBOL: &[u8] = b"_obel_create_plugin\0";
+
+/// Describes the build of a dynamic plugin library. Exported by [`dynamic_plugin!`](macro@crate::dynamic_plugin).
+///
+/// It is returned through the C ABI, so that it can be read before knowing whether the
+/// library was built by a compatible compiler and links the same engine.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicPluginDeclaration {
+    /// The [`DYNAMIC_PLUGIN_ABI_VERSION`] the library was built with.
+    pub abi_version: u32,
+    /// The engine version the library was built against, i.e. the version of `obel_api`.
+    pub obel_version: *const c_char,
+    /// The [`OBEL_RUSTC_VERSION`] the library was built with.
+    pub rustc_version: *const c_char,
+    /// Identifies the copy of the engine the library links.
+    pub engine: *const u8,
+}
+
+impl DynamicPluginDeclaration {
+    /// The declaration of the current build, against the engine version `obel_version`.
+    pub fn new(obel_version: &'static CStr) -> Self {
+        Self {
+            abi_version: DYNAMIC_PLUGIN_ABI_VERSION,
+            obel_version: obel_version.as_ptr(),
+            rustc_version: OBEL_RUSTC_VERSION.as_ptr(),
+            engine: &raw const ENGINE,
+        }
+    }
+}
+
+/// Exports a [`Plugin`] from a `dylib` crate, to be loaded by a [`DynamicPluginLoader`].
+///
+/// Plugins use the `obel_api::dynamic_plugin!` wrapper, which passes the engine version
+/// `obel_version`. The plugin expression is evaluated each time the library is loaded.
+#[macro_export]
+macro_rules! dynamic_plugin {
+    ($plugin:expr, $obel_version:expr) => {
+        #[doc(hidden)]
+        #[expect(unsafe_code, reason = "Exported symbols must not be mangled.")]
+        #[unsafe(no_mangle)]
+        pub extern "C" fn _obel_plugin_declaration() -> $crate::DynamicPluginDeclaration {
+            $crate::DynamicPluginDeclaration::new($obel_version)
+        }
+
+        #[doc(hidden)]
+        #[expect(unsafe_code, reason = "Exported symbols must not be mangled.")]
+        #[unsafe(no_mangle)]
+        pub fn _obel_create_plugin() -> $crate::__macro_exports::Box<dyn $crate::Plugin> {
+            $crate::__macro_exports::Box::new($plugin)
+        }
+    };
+}
+
+/// An error produced while loading a dynamic plugin.
+#[derive(Debug, Error)]
+pub enum DynamicPluginError {
+    /// The library could not be opened, or lacks an exported symbol.
+    #[error("failed to load dynamic plugin: {0}")]
+    Library(#[from] libloading::Error),
+    /// The library was built against another version of the plugin interface.
+    #[error("dynamic plugin ABI version {found} is incompatible with {expected}")]
+    AbiMismatch {
+        /// The [`DYNAMIC_PLUGIN_ABI_VERSION`] of the app.
+        expected: u32,
+        /// The ABI version of the library.
+        found: u32,
+    },
+    /// The library was built against another engine version.
+    #[error("dynamic plugin built for obel {found} is incompatible with obel {expected}")]
+    VersionMismatch {
+        /// The engine version of the app.
+        expected: String,
+        /// The engine version of the library.
+        found: String,
+    },
+    /// The library was built by another compiler.
+    #[error("dynamic plugin built with {found} is incompatible with {expected}")]
+    RustcMismatch {
+        /// The [`OBEL_RUSTC_VERSION`] of the app.
+        expected: String,
+        /// The compiler version of the library.
+        found: String,
+    },
+    /// The library links its own copy of the engine instead of sharing the app's, so the
+    /// plugin would see other globals, e.g. task pools and timers, than the app.
+    #[error(
+        "dynamic plugin does not share the app's engine, both must enable `obel/dynamic_linking`"
+    )]
+    EngineNotShared,
+    /// The plugin could not be added to the app.
+    #[error("failed to add dynamic plugin: {0}")]
+    App(#[from] AppError),
+    /// The library is already loaded.
+    #[error("dynamic plugin {0:?} is already loaded")]
+    AlreadyLoaded(PathBuf),
+    /// The library is not loaded.
+    #[error("dynamic plugin {0:?} is not loaded")]
+    NotLoaded(PathBuf),
+}
+
+/// Checks a [`DynamicPluginDeclaration`] against the current build, of engine version
+/// `obel_version`.
+///
+/// # Safety
+///
+/// The version pointers of `declaration` must point to valid NUL-terminated strings, which
+/// holds for any declaration with a matching `abi_version`.
+unsafe fn check_declaration(
+    declaration: &DynamicPluginDeclaration,
+    expected_version: &CStr,
+) -> Result<(), DynamicPluginError> {
+    if declaration.abi_version != DYNAMIC_PLUGIN_ABI_VERSION {
+        return Err(DynamicPluginError::AbiMismatch {
+            expected: DYNAMIC_PLUGIN_ABI_VERSION,
+            found: declaration.abi_version,
+        });
+    }
+    // SAFETY: the ABI version matches, so the pointers come from `DynamicPluginDeclaration::current`.
+    let (obel_version, rustc_version) = unsafe {
+        (CStr::from_ptr(declaration.obel_version), CStr::from_ptr(declaration.rustc_version))
+    };
+    if obel_version != expected_version {
+        return Err(DynamicPluginError::VersionMismatch {
+            expected: expected_version.to_string_lossy().into_owned(),
+            found: obel_version.to_string_lossy().into_owned(),
+        });
+    }
+    if rustc_version != OBEL_RUSTC_VERSION {
+        return Err(DynamicPluginError::RustcMismatch {
+            expected: OBEL_RUSTC_VERSION.to_string_lossy().into_owned(),
+            found: rustc_version.to_string_lossy().into_owned(),
+        });
+    }
+    if !core::ptr::eq(declaration.engine, &raw const ENGINE) {
+        return Err(DynamicPluginError::EngineNotShared);
+    }
+    Ok(())
+}
+
+/// A plugin created by a library. Kept behind an `Arc` of a sized type, so that dropping a
+/// [`DynamicPluginProxy`]'s `Weak` never reads the plugin's vtable, which lives in the library.
+struct PluginInstance {
+    plugin: Box<dyn Plugin>,
+    /// Whether the plugin was loaded into a finished app and waits to be
+    /// [ready](Plugin::ready) before being finished by [`DynamicPluginLoader::finish_pending`].
+    pending: AtomicBool,
+}
+
+struct LoadedPlugin {
+    path: PathBuf,
+    // Declared before `library`: the plugin's code and vtable live in the library, so the
+    // plugin must be dropped first.
+    plugin: Arc<PluginInstance>,
+    _library: Library,
+}
+
+/// Stands for a dynamic plugin in the [`App`]'s plugin registry, so that it goes through the
+/// same lifecycle as the other plugins, and forwards to the plugin owned by the
+/// [`DynamicPluginLoader`].
+///
+/// Once the plugin has been dropped, e.g. because the loader was dropped without unloading
+/// it, the proxy does nothing.
+struct DynamicPluginProxy {
+    path: PathBuf,
+    name: String,
+    is_unique: bool,
+    dependencies: Vec<String>,
+    plugin: Weak<PluginInstance>,
+}
+
+impl Plugin for DynamicPluginProxy {
+    fn build(&self, app: &mut App) {
+        if let Some(plugin) = self.plugin.upgrade() {
+            plugin.plugin.build(app);
+        }
+    }
+
+    fn ready(&self, app: &App) -> bool {
+        self.plugin.upgrade().is_none_or(|plugin| plugin.plugin.ready(app))
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(plugin) = self.plugin.upgrade() {
+            plugin.plugin.finish(app);
+        }
+    }
+
+    fn cleanup(&self, app: &mut App) {
+        // a pending plugin is cleaned up by the loader once it is finished
+        if let Some(plugin) = self.plugin.upgrade() {
+            if !plugin.pending.load(Ordering::Acquire) {
+                plugin.plugin.cleanup(app);
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_unique(&self) -> bool {
+        self.is_unique
+    }
+
+    fn dependencies(&self) -> Vec<&str> {
+        self.dependencies.iter().map(String::as_str).collect()
+    }
+}
+
+/// Loads gameplay [`Plugin`]s from shared libraries at runtime, and unloads or reloads them
+/// between frames.
+///
+/// The library is a `dylib` crate exporting its plugin with `obel::dynamic_plugin!`, built by
+/// the same compiler against the same engine version as the app. The app and the library must
+/// both enable `obel/dynamic_linking`, so that they share a single, dynamically linked copy of
+/// the engine and its globals, e.g. the task pools. All of this is checked when loading.
+///
+/// Loaded plugins are added to the [`App`] like any other plugin: their
+/// [`dependencies`](Plugin::dependencies) are checked, and [`ready`](Plugin::ready),
+/// [`finish`](Plugin::finish) and [`cleanup`](Plugin::cleanup) are called by the app. The
+/// plugins themselves are owned by the loader, so that they can be dropped before their
+/// library is closed.
+///
+/// Apps create their loader with `obel::dynamic_plugin_loader()`, which knows the engine
+/// version:
+///
+/// ```ignore
+/// let mut app = App::new();
+/// let mut loader = obel::dynamic_plugin_loader();
+///
+/// // SAFETY: the library is a trusted plugin built alongside this app.
+/// unsafe { loader.load(&mut app, "target/debug/libgameplay.so") }.unwrap();
+///
+/// // ... after rebuilding the library, between two frames:
+/// unsafe { loader.reload(&mut app, "target/debug/libgameplay.so") }.unwrap();
+/// loader.finish_pending(&mut app);
+/// ```
+pub struct DynamicPluginLoader {
+    obel_version: &'static CStr,
+    loaded: Vec<LoadedPlugin>,
+}
+
+impl DynamicPluginLoader {
+    /// Creates a loader accepting plugins built against the engine version `obel_version`.
+    pub fn new(obel_version: &'static CStr) -> Self {
+        Self {
+            obel_version,
+            loaded: Vec::new(),
+        }
+    }
+
+    /// Loads the plugin exported by the library at `path` and adds it to `app`.
+    ///
+    /// If the app already finished its setup, its dependencies are checked before building it.
+    /// It is then finished and cleaned up right away if it is [ready](Plugin::ready), or else
+    /// by a later call to [`finish_pending`](Self::finish_pending). Returns the
+    /// [`Plugin::name`] of the loaded plugin.
+    ///
+    /// # Safety
+    ///
+    /// - The library runs arbitrary code when opened and when its plugin is built; it must be
+    ///   trusted.
+    /// - Nothing the plugin registers in `app` may reference code or data of the library
+    ///   after [`unload`](Self::unload), e.g. a runner set with [`App::set_runner`].
+    pub unsafe fn load(
+        &mut self,
+        app: &mut App,
+        path: impl AsRef<Path>,
+    ) -> Result<&str, DynamicPluginError> {
+        let path = path.as_ref();
+        if self.index_of(path).is_some() {
+            return Err(DynamicPluginError::AlreadyLoaded(path.to_owned()));
+        }
+
+        // SAFETY: the caller guarantees the library is trusted. The declaration is read through
+        // the C ABI and checked before calling any Rust ABI symbol.
+        let loaded = unsafe {
+            let library = Library::new(path)?;
+            let declaration: Symbol<extern "C" fn() -> DynamicPluginDeclaration> =
+                library.get(DECLARATION_SYMBOL)?;
+            check_declaration(&declaration(), self.obel_version)?;
+            let create: Symbol<fn() -> Box<dyn Plugin>> = library.get(CREATE_SYMBOL)?;
+            let plugin = create();
+            LoadedPlugin {
+                path: path.to_owned(),
+                plugin: Arc::new(PluginInstance {
+                    plugin,
+                    pending: AtomicBool::new(false),
+                }),
+                _library: library,
+            }
+        };
+        let plugin = &loaded.plugin.plugin;
+
+        // `App::finish` already checked the dependencies of the other plugins
+        let state = app.plugins_state();
+        if state >= PluginsState::Finished {
+            if let Some(dependency) =
+                plugin.dependencies().into_iter().find(|name| !app.is_plugin_name_added(name))
+            {
+                return Err(AppError::MissingPluginDependency {
+                    plugin_name: plugin.name().to_owned(),
+                    dependency: dependency.to_owned(),
+                }
+                .into());
+            }
+        }
+
+        log::info!("loaded dynamic plugin {} from {path:?}", plugin.name());
+        app.add_boxed_plugin(Box::new(DynamicPluginProxy {
+            path: path.to_owned(),
+            name: plugin.name().to_owned(),
+            is_unique: plugin.is_unique(),
+            dependencies: plugin.dependencies().into_iter().map(ToOwned::to_owned).collect(),
+            plugin: Arc::downgrade(&loaded.plugin),
+        }))?;
+        if state >= PluginsState::Finished {
+            loaded.plugin.pending.store(true, Ordering::Release);
+            Self::try_finish(&loaded, app);
+        }
+
+        self.loaded.push(loaded);
+        Ok(self.loaded.last().unwrap().plugin.plugin.name())
+    }
+
+    /// Finishes and cleans up the plugins loaded into a finished app that have become
+    /// [ready](Plugin::ready) since. Call it once per frame, between two updates.
+    ///
+    /// Returns the number of plugins still waiting to be ready.
+    pub fn finish_pending(&mut self, app: &mut App) -> usize {
+        self.loaded.iter().filter(|loaded| !Self::try_finish(loaded, app)).count()
+    }
+
+    /// Returns whether the plugin loaded from `path` waits to be finished by
+    /// [`finish_pending`](Self::finish_pending).
+    pub fn is_pending(&self, path: impl AsRef<Path>) -> bool {
+        self.index_of(path.as_ref())
+            .is_some_and(|index| self.loaded[index].plugin.pending.load(Ordering::Acquire))
+    }
+
+    /// Finishes and cleans up a pending plugin if it is ready. Returns `false` if it is still
+    /// pending.
+    fn try_finish(loaded: &LoadedPlugin, app: &mut App) -> bool {
+        let instance = &loaded.plugin;
+        if !instance.pending.load(Ordering::Acquire) {
+            return true;
+        }
+        if !instance.plugin.ready(app) {
+            return false;
+        }
+        instance.plugin.finish(app);
+        if app.plugins_state() >= PluginsState::Cleaned {
+            instance.plugin.cleanup(app);
+        }
+        instance.pending.store(false, Ordering::Release);
+        true
+    }
+
+    /// Removes the plugin loaded from `path` from `app`, drops it and closes its library.
+    pub fn unload(
+        &mut self,
+        app: &mut App,
+        path: impl AsRef<Path>,
+    ) -> Result<(), DynamicPluginError> {
+        let path = path.as_ref();
+        let index =
+            self.index_of(path).ok_or_else(|| DynamicPluginError::NotLoaded(path.to_owned()))?;
+        let loaded = self.loaded.remove(index);
+        log::info!("unloading dynamic plugin {} from {path:?}", loaded.plugin.plugin.name());
+        app.remove_plugins(|plugin| {
+            plugin.downcast_ref::<DynamicPluginProxy>().is_some_and(|proxy| proxy.path == path)
+        });
+        Ok(())
+    }
+
+    /// Unloads the plugin loaded from `path`, then loads the library at `path` again, e.g.
+    /// after it has been rebuilt.
+    ///
+    /// # Safety
+    ///
+    /// See [`load`](Self::load).
+    pub unsafe fn reload(
+        &mut self,
+        app: &mut App,
+        path: impl AsRef<Path>,
+    ) -> Result<&str, DynamicPluginError> {
+        let path = path.as_ref();
+        self.unload(app, path)?;
+        // SAFETY: forwarded to the caller.
+        unsafe { self.load(app, path) }
+    }
+
+    /// Returns whether a plugin is loaded from `path`.
+    pub fn is_loaded(&self, path: impl AsRef<Path>) -> bool {
+        self.index_of(path.as_ref()).is_some()
+    }
+
+    /// Returns the paths of the loaded libraries, in loading order.
+    pub fn loaded_paths(&self) -> impl Iterator<Item = &Path> {
+        self.loaded.iter().map(|loaded| loaded.path.as_path())
+    }
+
+    fn index_of(&self, path: &Path) -> Option<usize> {
+        self.loaded.iter().position(|loaded| loaded.path == path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION: &CStr = c"1.2.3";
+
+    #[test]
+    fn current_declaration_is_compatible() {
+        let declaration = DynamicPluginDeclaration::new(VERSION);
+        // SAFETY: the declaration comes from `new`.
+        assert!(unsafe { check_declaration(&declaration, VERSION) }.is_ok());
+    }
+
+    #[test]
+    fn rejects_other_abi_version() {
+        let declaration = DynamicPluginDeclaration {
+            abi_version: DYNAMIC_PLUGIN_ABI_VERSION + 1,
+            ..DynamicPluginDeclaration::new(VERSION)
+        };
+        // SAFETY: the pointers are not read when the ABI version differs.
+        let result = unsafe { check_declaration(&declaration, VERSION) };
+        assert!(matches!(result, Err(DynamicPluginError::AbiMismatch { .. })));
+    }
+
+    #[test]
+    fn rejects_other_engine_and_compiler_versions() {
+        let declaration = DynamicPluginDeclaration::new(c"0.0.0");
+        // SAFETY: the pointers point to NUL-terminated strings.
+        let result = unsafe { check_declaration(&declaration, VERSION) };
+        assert!(
+            matches!(result, Err(DynamicPluginError::VersionMismatch { found, .. }) if found == "0.0.0")
+        );
+
+        let declaration = DynamicPluginDeclaration {
+            rustc_version: c"rustc 1.0.0".as_ptr(),
+            ..DynamicPluginDeclaration::new(VERSION)
+        };
+        // SAFETY: the pointers point to NUL-terminated strings.
+        let result = unsafe { check_declaration(&declaration, VERSION) };
+        assert!(matches!(result, Err(DynamicPluginError::RustcMismatch { .. })));
+    }
+
+    #[test]
+    fn rejects_statically_linked_engine() {
+        static OTHER_ENGINE: u8 = 0;
+        let declaration = DynamicPluginDeclaration {
+            engine: &raw const OTHER_ENGINE,
+            ..DynamicPluginDeclaration::new(VERSION)
+        };
+        // SAFETY: the pointers point to NUL-terminated strings.
+        let result = unsafe { check_declaration(&declaration, VERSION) };
+        assert!(matches!(result, Err(DynamicPluginError::EngineNotShared)));
+    }
+
+    #[test]
+    fn missing_library_fails_to_load() {
+        let mut app = App::new();
+        let mut loader = DynamicPluginLoader::new(VERSION);
+        // SAFETY: the library does not exist, so no code is run.
+        let result = unsafe { loader.load(&mut app, "does/not/exist.so") };
+        assert!(matches!(result, Err(DynamicPluginError::Library(_))));
+        assert!(!loader.is_loaded("does/not/exist.so"));
+        assert!(matches!(
+            loader.unload(&mut app, "does/not/exist.so"),
+            Err(DynamicPluginError::NotLoaded(_))
+        ));
+        assert_eq!(loader.finish_pending(&mut app), 0);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{App, AppExit, PluginsState};
+use core::time::Duration;
+use obel_tasks::{MockClock, Timers};
+
+/// Drives an [`App`] frame by frame on a [`MockClock`], without a window or event loop.
+///
+/// This is meant for tests running deterministic scenarios: every frame first advances the
+/// clock by a fixed [`frame_time`](Self::frame_time), firing the [`timers`](Self::timers) that
+/// are then due, then runs [`App::update`], which runs the tasks those timers woke up.
+///
+/// Plugins are finished and cleaned up before the first frame after they are all
+/// [ready](crate::Plugin::ready), as a runner would do.
+///
+/// ```
+/// # use obel_app::{App, AppExit, HeadlessApp, TaskPoolPlugin};
+/// # use obel_tasks::ComputeTaskPool;
+/// # use core::time::Duration;
+/// let mut app = App::new();
+/// app.add_plugins(TaskPoolPlugin);
+/// let mut headless = HeadlessApp::new(app).with_frame_time(Duration::from_millis(10));
+///
+/// // exits after 50ms of mocked time, however long the frames really take
+/// let signal = headless.app().exit_signal();
+/// let timers = headless.timers().clone();
+/// ComputeTaskPool::get()
+///     .spawn_local(async move {
+///         timers.sleep(Duration::from_millis(50)).await;
+///         signal.exit(AppExit::Success);
+///     })
+///     .detach();
+///
+/// assert_eq!(headless.run_frames(100), Some(AppExit::Success));
+/// assert_eq!(headless.frame(), 5);
+/// assert_eq!(headless.clock().elapsed(), Duration::from_millis(50));
+/// ```
+pub struct HeadlessApp {
+    app: App,
+    clock: MockClock,
+    timers: Timers,
+    frame_time: Duration,
+    frame: u64,
+}
+
+impl HeadlessApp {
+    /// The default [`frame_time`](Self::frame_time), corresponding to 60 Hz.
+    pub const DEFAULT_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+    /// Drives `app` on a new [`MockClock`] at zero.
+    pub fn new(app: App) -> Self {
+        Self::with_clock(app, MockClock::new())
+    }
+
+    /// Drives `app` on `clock`, e.g. to share it with [`Timers`] created by the test.
+    pub fn with_clock(app: App, clock: MockClock) -> Self {
+        Self {
+            app,
+            timers: Timers::with_mock_clock(&clock),
+            clock,
+            frame_time: Self::DEFAULT_FRAME_TIME,
+            frame: 0,
+        }
+    }
+
+    /// Sets the time the clock advances by each frame.
+    pub fn with_frame_time(mut self, frame_time: Duration) -> Self {
+        self.frame_time = frame_time;
+        self
+    }
+
+    /// Returns the time the clock advances by each frame.
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Returns the driven app.
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// Returns the driven app.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Returns the clock advanced by each frame.
+    pub fn clock(&self) -> &MockClock {
+        &self.clock
+    }
+
+    /// Returns timers on the [`clock`](Self::clock), for the futures run by the app.
+    pub fn timers(&self) -> &Timers {
+        &self.timers
+    }
+
+    /// Returns the number of frames run so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Runs one frame. Returns the [`AppExit`] requested during it, if any.
+    pub fn update(&mut self) -> Option<AppExit> {
+        if self.app.plugins_state() == PluginsState::Ready {
+            self.app.finish();
+            self.app.cleanup();
+        }
+        self.clock.advance(self.frame_time);
+        self.app.update();
+        self.frame += 1;
+        self.app.should_exit()
+    }
+
+    /// Runs up to `frames` frames, stopping after the first one requesting an exit. Returns the
+    /// requested [`AppExit`], if any.
+    pub fn run_frames(&mut self, frames: u64) -> Option<AppExit> {
+        (0..frames).find_map(|_| self.update())
+    }
+
+    /// Runs frames until `done` returns `true` for the app, at most `max_frames` of them.
+    /// Returns whether `done` was reached.
+    pub fn run_until(&mut self, max_frames: u64, mut done: impl FnMut(&App) -> bool) -> bool {
+        for _ in 0..max_frames {
+            if done(&self.app) {
+                return true;
+            }
+            self.update();
+        }
+        done(&self.app)
+    }
+
+    /// Returns the driven app, e.g. to [run](App::run) it.
+    pub fn into_app(self) -> App {
+        self.app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeadlessApp;
+    use crate::{App, AppExit, Plugin, PluginsState, TaskPoolPlugin};
+    use core::{
+        sync::atomic::{AtomicBool, AtomicU32, Ordering},
+        time::Duration,
+    };
+    use obel_tasks::ComputeTaskPool;
+
+    #[test]
+    fn frames_advance_the_clock() {
+        let mut headless = HeadlessApp::new(App::new()).with_frame_time(Duration::from_millis(20));
+        assert_eq!(headless.run_frames(3), None);
+        assert_eq!(headless.frame(), 3);
+        assert_eq!(headless.clock().elapsed(), Duration::from_millis(60));
+        assert_eq!(headless.timers().now(), Duration::from_millis(60));
+    }
+
+    #[test]
+    fn timers_wake_tasks_on_the_frame_they_are_due() {
+        static FIRED_AT: AtomicU32 = AtomicU32::new(0);
+
+        let mut app = App::new();
+        app.add_plugins(TaskPoolPlugin);
+        let mut headless = HeadlessApp::new(app).with_frame_time(Duration::from_millis(10));
+        let timers = headless.timers().clone();
+        ComputeTaskPool::get()
+            .spawn_local(async move {
+                timers.sleep(Duration::from_millis(35)).await;
+                FIRED_AT.store(timers.now().as_millis() as u32, Ordering::SeqCst);
+            })
+            .detach();
+
+        assert!(headless.run_until(10, |_| FIRED_AT.load(Ordering::SeqCst) != 0));
+        assert_eq!(headless.frame(), 4);
+        assert_eq!(FIRED_AT.load(Ordering::SeqCst), 40);
+    }
+
+    #[test]
+    fn stops_on_exit() {
+        let mut headless = HeadlessApp::new(App::new());
+        headless.run_frames(2);
+        headless.app().exit_signal().exit(AppExit::from_code(4));
+        assert_eq!(headless.run_frames(10), Some(AppExit::from_code(4)));
+        assert_eq!(headless.frame(), 3);
+    }
+
+    #[test]
+    fn finishes_plugins_once_ready() {
+        static READY: AtomicBool = AtomicBool::new(false);
+        static FINISHED: AtomicBool = AtomicBool::new(false);
+
+        struct WaitingPlugin;
+
+        impl Plugin for WaitingPlugin {
+            fn build(&self, _app: &mut App) {}
+
+            fn ready(&self, _app: &App) -> bool {
+                READY.load(Ordering::SeqCst)
+            }
+
+            fn finish(&self, _app: &mut App) {
+                FINISHED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins(WaitingPlugin);
+        let mut headless = HeadlessApp::new(app);
+        headless.update();
+        assert!(!FINISHED.load(Ordering::SeqCst));
+
+        READY.store(true, Ordering::SeqCst);
+        headless.update();
+        assert!(FINISHED.load(Ordering::SeqCst));
+        assert_eq!(headless.app().plugins_state(), PluginsState::Cleaned);
+    }
+}
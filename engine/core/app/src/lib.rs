@@ -2,3 +2,48 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 // #![doc(html_logo_url = "assets/icon.png", html_favicon_url = "assets/icon.png")]
 #![no_std] // tells the compiler "don't automatically link std"
+
+//! This crate is about everything concerning the highest-level, application layer of an Obel app.
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod app;
+#[cfg(feature = "dynamic_plugin")]
+mod dynamic_plugin;
+mod headless;
+#[cfg(feature = "manifest")]
+mod manifest;
+mod plugin;
+mod plugin_group;
+mod schedule_runner;
+mod task_pool_plugin;
+
+pub use app::*;
+#[cfg(feature = "dynamic_plugin")]
+pub use dynamic_plugin::*;
+pub use headless::*;
+#[cfg(feature = "manifest")]
+pub use manifest::*;
+pub use plugin::*;
+pub use plugin_group::*;
+pub use schedule_runner::*;
+pub use task_pool_plugin::*;
+
+#[doc(hidden)]
+pub mod __macro_exports {
+    pub use alloc::boxed::Box;
+}
+
+/// The app prelude.
+///
+/// This includes the most common types in this crate, re-exported for your convenience.
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{
+        app::{App, AppExit},
+        plugin::Plugin,
+        plugin_group::{PluginGroup, PluginGroupBuilder},
+    };
+}
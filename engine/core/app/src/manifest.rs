@@ -0,0 +1,425 @@
+use crate::{App, Plugin};
+use alloc::{borrow::ToOwned, format, string::String};
+use serde::{Deserialize, Serialize};
+use std::{env, ffi::OsStr, fs, io, path::Path};
+use thiserror::Error;
+
+/// The prefix of the environment variables overriding [`ProjectManifest`] values.
+pub const MANIFEST_ENV_PREFIX: &str = "OBEL_";
+
+/// A project manifest, usually stored as `game.json` next to the game.
+///
+/// ```json
+/// {
+///   "entryScene": "home.scene",
+///   "game_parameters": {
+///     "targetPlatform": "windows",
+///     "buildVersion": "v1.0.0"
+///   }
+/// }
+/// ```
+///
+/// Values are resolved in order, each source overriding the previous one:
+///
+/// 1. the manifest file, see [`ProjectManifest::load`],
+/// 2. environment variables, see [`ProjectManifest::apply_env_overrides`],
+/// 3. command line flags, see [`ProjectManifest::apply_cli_overrides`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    /// The scene loaded when the game starts.
+    #[serde(rename = "entryScene")]
+    pub entry_scene: String,
+    /// Parameters describing the build.
+    #[serde(default)]
+    pub game_parameters: GameParameters,
+}
+
+/// The `game_parameters` section of a [`ProjectManifest`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameParameters {
+    /// The platform the game is built for, e.g. `windows`.
+    #[serde(default)]
+    pub target_platform: String,
+    /// The version of the build, e.g. `v1.0.0`.
+    #[serde(default)]
+    pub build_version: String,
+}
+
+/// An error produced while loading a [`ProjectManifest`].
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    /// The manifest file could not be read.
+    #[error("failed to read manifest: {0}")]
+    Io(#[from] io::Error),
+    /// The manifest is not valid JSON.
+    #[error("failed to parse JSON manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The manifest is not valid RON.
+    #[error("failed to parse RON manifest: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    /// The manifest file extension is neither `json` nor `ron`.
+    #[error("unsupported manifest format {extension:?}, expected `json` or `ron`")]
+    UnsupportedFormat {
+        /// The extension of the manifest file.
+        extension: String,
+    },
+    /// A command line flag overriding a manifest value was given without a value.
+    #[error("missing value for manifest flag `{flag}`")]
+    MissingFlagValue {
+        /// The flag missing its value.
+        flag: String,
+    },
+    /// A command line flag overriding a manifest value was given a value that is not valid
+    /// UTF-8.
+    #[error("value of manifest flag `{flag}` is not valid UTF-8")]
+    NonUtf8FlagValue {
+        /// The flag with the invalid value.
+        flag: String,
+    },
+}
+
+/// A [`ProjectManifest`] field that can be overridden from the environment or the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManifestOverride {
+    EntryScene,
+    TargetPlatform,
+    BuildVersion,
+}
+
+impl ManifestOverride {
+    const ALL: [Self; 3] = [Self::EntryScene, Self::TargetPlatform, Self::BuildVersion];
+
+    /// The environment variable name, without [`MANIFEST_ENV_PREFIX`].
+    fn env_suffix(self) -> &'static str {
+        match self {
+            Self::EntryScene => "ENTRY_SCENE",
+            Self::TargetPlatform => "TARGET_PLATFORM",
+            Self::BuildVersion => "BUILD_VERSION",
+        }
+    }
+
+    fn flag(self) -> &'static str {
+        match self {
+            Self::EntryScene => "--entry-scene",
+            Self::TargetPlatform => "--target-platform",
+            Self::BuildVersion => "--build-version",
+        }
+    }
+
+    fn from_flag(flag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.flag() == flag)
+    }
+
+    /// Returns the key whose `--flag=value` form `arg` uses, if any.
+    fn from_inline_arg(arg: &OsStr) -> Option<Self> {
+        let arg = arg.as_encoded_bytes();
+        Self::ALL.into_iter().find(|key| {
+            arg.strip_prefix(key.flag().as_bytes()).is_some_and(|rest| rest.starts_with(b"="))
+        })
+    }
+}
+
+impl ProjectManifest {
+    /// Parses a manifest from a JSON string.
+    pub fn from_json_str(s: &str) -> Result<Self, ManifestError> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Parses a manifest from a RON string.
+    pub fn from_ron_str(s: &str) -> Result<Self, ManifestError> {
+        Ok(ron::from_str(s)?)
+    }
+
+    /// Reads a manifest file, choosing the format from its `json` or `ron` extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        match extension {
+            "json" => Self::from_json_str(&fs::read_to_string(path)?),
+            "ron" => Self::from_ron_str(&fs::read_to_string(path)?),
+            _ => Err(ManifestError::UnsupportedFormat {
+                extension: extension.to_owned(),
+            }),
+        }
+    }
+
+    /// Overrides values from the `OBEL_ENTRY_SCENE`, `OBEL_TARGET_PLATFORM` and
+    /// `OBEL_BUILD_VERSION` environment variables, when set.
+    pub fn apply_env_overrides(&mut self) {
+        self.apply_overrides_with(|suffix| env::var(format!("{MANIFEST_ENV_PREFIX}{suffix}")).ok());
+    }
+
+    /// Overrides values from the `--entry-scene`, `--target-platform` and `--build-version`
+    /// flags in `args`.
+    ///
+    /// Both `--flag value` and `--flag=value` are accepted. Other arguments are ignored, even if
+    /// they are not valid UTF-8, so the full command line can be passed, e.g.
+    /// `std::env::args_os().skip(1)`.
+    ///
+    /// Returns [`ManifestError::MissingFlagValue`] if a flag is last or followed by another
+    /// flag. A value starting with `--` must be given as `--flag=value`. Returns
+    /// [`ManifestError::NonUtf8FlagValue`] if the value of a flag is not valid UTF-8.
+    ///
+    /// ```
+    /// # use obel_app::ProjectManifest;
+    /// let mut manifest = ProjectManifest::default();
+    /// manifest
+    ///     .apply_cli_overrides(["--entry-scene", "arena.scene", "--build-version=v1.2.0"])
+    ///     .unwrap();
+    /// assert_eq!(manifest.entry_scene, "arena.scene");
+    /// assert_eq!(manifest.game_parameters.build_version, "v1.2.0");
+    /// ```
+    pub fn apply_cli_overrides<I, S>(&mut self, args: I) -> Result<(), ManifestError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            let Some(arg) = arg.to_str() else {
+                if let Some(key) = ManifestOverride::from_inline_arg(arg) {
+                    return Err(ManifestError::NonUtf8FlagValue {
+                        flag: key.flag().to_owned(),
+                    });
+                }
+                continue;
+            };
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_owned())),
+                None => (arg, None),
+            };
+            let Some(key) = ManifestOverride::from_flag(flag) else {
+                continue;
+            };
+            let value = match inline_value {
+                Some(value) => value,
+                None => {
+                    let value = args.next();
+                    let value = value.as_ref().map(|value| value.as_ref());
+                    match value.map(OsStr::to_str) {
+                        Some(Some(value)) if !value.starts_with("--") => value.to_owned(),
+                        Some(None) => {
+                            return Err(ManifestError::NonUtf8FlagValue {
+                                flag: flag.to_owned(),
+                            });
+                        }
+                        // another flag is not a value, e.g. `--entry-scene --target-platform=web`
+                        _ => {
+                            return Err(ManifestError::MissingFlagValue {
+                                flag: flag.to_owned(),
+                            });
+                        }
+                    }
+                }
+            };
+            *self.field_mut(key) = value;
+        }
+        Ok(())
+    }
+
+    fn apply_overrides_with(&mut self, mut lookup: impl FnMut(&str) -> Option<String>) {
+        for key in ManifestOverride::ALL {
+            if let Some(value) = lookup(key.env_suffix()) {
+                *self.field_mut(key) = value;
+            }
+        }
+    }
+
+    fn field_mut(&mut self, key: ManifestOverride) -> &mut String {
+        match key {
+            ManifestOverride::EntryScene => &mut self.entry_scene,
+            ManifestOverride::TargetPlatform => &mut self.game_parameters.target_platform,
+            ManifestOverride::BuildVersion => &mut self.game_parameters.build_version,
+        }
+    }
+}
+
+/// Loads the [`ProjectManifest`] of the game and makes it available to other plugins.
+///
+/// The manifest is resolved when the plugin is created, so that a missing or malformed
+/// manifest is reported to the caller instead of panicking inside [`App::add_plugins`].
+/// Other plugins read it back with [`ManifestPlugin::get`].
+///
+/// ```no_run
+/// # use obel_app::{App, ManifestPlugin};
+/// let mut app = App::new();
+/// app.add_plugins(ManifestPlugin::load("game.json").expect("invalid game manifest"));
+///
+/// let manifest = ManifestPlugin::get(&app).unwrap();
+/// println!("starting {}", manifest.entry_scene);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManifestPlugin {
+    manifest: ProjectManifest,
+}
+
+impl ManifestPlugin {
+    /// Loads the manifest at `path`, then applies environment variable and command line
+    /// overrides from the current process.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let mut manifest = ProjectManifest::load(path)?;
+        manifest.apply_env_overrides();
+        manifest.apply_cli_overrides(env::args_os().skip(1))?;
+        Ok(Self::from_manifest(manifest))
+    }
+
+    /// Uses an already resolved manifest.
+    pub fn from_manifest(manifest: ProjectManifest) -> Self {
+        Self {
+            manifest,
+        }
+    }
+
+    /// Returns the manifest.
+    pub fn manifest(&self) -> &ProjectManifest {
+        &self.manifest
+    }
+
+    /// Returns the manifest of the [`ManifestPlugin`] added to `app`, if any.
+    pub fn get(app: &App) -> Option<&ProjectManifest> {
+        app.get_added_plugins::<Self>().into_iter().next().map(Self::manifest)
+    }
+}
+
+impl Plugin for ManifestPlugin {
+    fn build(&self, _app: &mut App) {
+        log::info!(
+            "loaded project manifest: entry scene {:?}, target platform {:?}, build version {:?}",
+            self.manifest.entry_scene,
+            self.manifest.game_parameters.target_platform,
+            self.manifest.game_parameters.build_version,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::App;
+
+    const GAME_JSON: &str = r#"{
+  "entryScene": "home.scene",
+  "game_parameters": {
+    "targetPlatform": "windows",
+    "buildVersion": "v1.0.0"
+  }
+}"#;
+
+    fn home() -> ProjectManifest {
+        ProjectManifest {
+            entry_scene: "home.scene".into(),
+            game_parameters: GameParameters {
+                target_platform: "windows".into(),
+                build_version: "v1.0.0".into(),
+            },
+        }
+    }
+
+    #[test]
+    fn parses_json() {
+        assert_eq!(ProjectManifest::from_json_str(GAME_JSON).unwrap(), home());
+    }
+
+    #[test]
+    fn parses_ron() {
+        let ron = r#"(
+            entryScene: "home.scene",
+            game_parameters: (targetPlatform: "windows", buildVersion: "v1.0.0"),
+        )"#;
+        assert_eq!(ProjectManifest::from_ron_str(ron).unwrap(), home());
+    }
+
+    #[test]
+    fn load_rejects_unknown_extension() {
+        assert!(matches!(
+            ProjectManifest::load("game.toml"),
+            Err(ManifestError::UnsupportedFormat { extension }) if extension == "toml"
+        ));
+    }
+
+    #[test]
+    fn load_reads_file() {
+        let path = env::temp_dir().join(format!("obel_app_manifest_{}.json", std::process::id()));
+        fs::write(&path, GAME_JSON).unwrap();
+        let manifest = ProjectManifest::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(manifest.unwrap(), home());
+    }
+
+    #[test]
+    fn env_overrides() {
+        let mut manifest = home();
+        manifest
+            .apply_overrides_with(|suffix| (suffix == "TARGET_PLATFORM").then(|| "linux".into()));
+        assert_eq!(manifest.game_parameters.target_platform, "linux");
+        assert_eq!(manifest.entry_scene, "home.scene");
+    }
+
+    #[test]
+    fn cli_overrides_take_both_forms_and_ignore_other_args() {
+        let mut manifest = home();
+        manifest
+            .apply_cli_overrides([
+                "--headless",
+                "--entry-scene",
+                "arena.scene",
+                "--target-platform=linux",
+            ])
+            .unwrap();
+        assert_eq!(manifest.entry_scene, "arena.scene");
+        assert_eq!(manifest.game_parameters.target_platform, "linux");
+        assert_eq!(manifest.game_parameters.build_version, "v1.0.0");
+    }
+
+    #[test]
+    fn cli_override_without_value_is_an_error() {
+        let mut manifest = home();
+        assert!(matches!(
+            manifest.apply_cli_overrides(["--build-version"]),
+            Err(ManifestError::MissingFlagValue { flag }) if flag == "--build-version"
+        ));
+    }
+
+    #[test]
+    fn cli_override_followed_by_flag_is_an_error() {
+        let mut manifest = home();
+        assert!(matches!(
+            manifest.apply_cli_overrides(["--entry-scene", "--target-platform=web"]),
+            Err(ManifestError::MissingFlagValue { flag }) if flag == "--entry-scene"
+        ));
+        manifest.apply_cli_overrides(["--entry-scene=--odd.scene"]).unwrap();
+        assert_eq!(manifest.entry_scene, "--odd.scene");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cli_overrides_skip_non_utf8_args_and_reject_non_utf8_values() {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+        let non_utf8 = || OsString::from_vec(b"caf\xe9".to_vec());
+        let mut manifest = home();
+        manifest.apply_cli_overrides([non_utf8(), "--entry-scene=arena.scene".into()]).unwrap();
+        assert_eq!(manifest.entry_scene, "arena.scene");
+
+        assert!(matches!(
+            manifest.apply_cli_overrides([OsString::from("--build-version"), non_utf8()]),
+            Err(ManifestError::NonUtf8FlagValue { flag }) if flag == "--build-version"
+        ));
+        let mut inline = OsString::from("--target-platform=");
+        inline.push(non_utf8());
+        assert!(matches!(
+            manifest.apply_cli_overrides([inline]),
+            Err(ManifestError::NonUtf8FlagValue { flag }) if flag == "--target-platform"
+        ));
+    }
+
+    #[test]
+    fn plugin_exposes_manifest() {
+        let mut app = App::new();
+        assert!(ManifestPlugin::get(&app).is_none());
+        app.add_plugins(ManifestPlugin::from_manifest(home()));
+        assert_eq!(ManifestPlugin::get(&app), Some(&home()));
+    }
+}
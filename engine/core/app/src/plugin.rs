@@ -0,0 +1,227 @@
+use crate::App;
+use alloc::vec::Vec;
+use core::any::Any;
+use downcast_rs::{Downcast, impl_downcast};
+
+/// A collection of Obel app logic and configuration.
+///
+/// Plugins configure an [`App`]. When an [`App`] registers a plugin,
+/// the plugin's [`Plugin::build`] function is run. By default, a plugin
+/// can only be added once to an [`App`].
+///
+/// If the plugin may need to be added twice or more, the function [`is_unique()`](Self::is_unique)
+/// should be overridden to return `false`. Plugins are considered duplicate if they have the same
+/// [`name()`](Self::name). The default `name()` implementation returns the type name, which means
+/// generic plugins with different type parameters will not be considered duplicates.
+///
+/// ## Lifecycle of a plugin
+///
+/// When adding a plugin to an [`App`]:
+/// * the app calls [`Plugin::build`] immediately, and register the plugin
+/// * once the app started, it will wait for all registered [`Plugin::ready`] to return `true`
+/// * it will then call all registered [`Plugin::finish`]
+/// * and call all registered [`Plugin::cleanup`]
+///
+/// ## Dependencies
+///
+/// A plugin can declare the plugins it relies on by overriding [`Plugin::dependencies`].
+/// Dependencies are checked when the app is finished, so they can be added in any order;
+/// a missing dependency is reported as [`AppError::MissingPluginDependency`].
+///
+/// ## Defining a plugin.
+///
+/// Most plugins are simply functions that add configuration to an [`App`].
+///
+/// ```
+/// # use obel_app::{App, Plugin};
+/// App::new().add_plugins(my_plugin);
+///
+/// // This function implements `Plugin`, along with every other `fn(&mut App)`.
+/// pub fn my_plugin(app: &mut App) {
+///     // ...
+/// }
+/// ```
+///
+/// For more advanced use cases, the `Plugin` trait can be implemented manually for a type.
+///
+/// ```
+/// # use obel_app::{App, Plugin};
+/// pub struct AccessibilityPlugin {
+///     pub flicker_damping: bool,
+///     // ...
+/// }
+///
+/// impl Plugin for AccessibilityPlugin {
+///     fn build(&self, app: &mut App) {
+///         if self.flicker_damping {
+///             // ...
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`AppError::MissingPluginDependency`]: crate::AppError::MissingPluginDependency
+pub trait Plugin: Downcast + Any + Send + Sync {
+    /// Configures the [`App`] to which this plugin is added.
+    fn build(&self, app: &mut App);
+
+    /// Has the plugin finished its setup? This can be useful for plugins that need something
+    /// asynchronous to happen before they can finish their setup, like the initialization of a renderer.
+    /// Once the plugin is ready, [`finish`](Plugin::finish) should be called.
+    fn ready(&self, _app: &App) -> bool {
+        true
+    }
+
+    /// Finish adding this plugin to the [`App`], once all plugins registered are ready. This can
+    /// be useful for plugins that depends on another plugin asynchronous setup, like the renderer.
+    fn finish(&self, _app: &mut App) {
+        // do nothing
+    }
+
+    /// Runs after all plugins are built and finished, but before the app starts running.
+    /// This can be useful if you have some resource that other plugins need during their build step,
+    /// but after build you want to remove it and send it to another thread.
+    fn cleanup(&self, _app: &mut App) {
+        // do nothing
+    }
+
+    /// Configures a name for the [`Plugin`] which is primarily used for checking plugin
+    /// uniqueness and debugging.
+    fn name(&self) -> &str {
+        core::any::type_name::<Self>()
+    }
+
+    /// If the plugin can be meaningfully instantiated several times in an [`App`],
+    /// override this method to return `false`.
+    fn is_unique(&self) -> bool {
+        true
+    }
+
+    /// The [`name()`](Self::name)s of the plugins this plugin relies on.
+    ///
+    /// Use [`plugin_name`] to refer to a plugin type with the default `name()`.
+    ///
+    /// ```
+    /// # use obel_app::{App, Plugin, plugin_name};
+    /// # struct PhysicsPlugin;
+    /// # impl Plugin for PhysicsPlugin { fn build(&self, _: &mut App) {} }
+    /// struct ProjectilePlugin;
+    ///
+    /// impl Plugin for ProjectilePlugin {
+    ///     fn build(&self, app: &mut App) {}
+    ///
+    ///     fn dependencies(&self) -> Vec<&str> {
+    ///         vec![plugin_name::<PhysicsPlugin>()]
+    ///     }
+    /// }
+    /// ```
+    fn dependencies(&self) -> Vec<&str> {
+        Vec::new()
+    }
+}
+
+impl_downcast!(Plugin);
+
+impl<T: Fn(&mut App) + Send + Sync + 'static> Plugin for T {
+    fn build(&self, app: &mut App) {
+        self(app);
+    }
+}
+
+/// Returns the default [`Plugin::name`] of the plugin type `P`.
+pub fn plugin_name<P: Plugin>() -> &'static str {
+    core::any::type_name::<P>()
+}
+
+/// Plugins state in the application
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord)]
+pub enum PluginsState {
+    /// Plugins are being added.
+    Adding,
+    /// All plugins already added are ready.
+    Ready,
+    /// Finish has been executed for all plugins added.
+    Finished,
+    /// Cleanup has been executed for all plugins added.
+    Cleaned,
+}
+
+/// A dummy plugin that's to temporarily occupy an entry in an app's plugin registry.
+pub(crate) struct PlaceholderPlugin;
+
+impl Plugin for PlaceholderPlugin {
+    fn build(&self, _app: &mut App) {}
+}
+
+/// Types that represent a set of [`Plugin`]s.
+///
+/// This is implemented for all types which implement [`Plugin`],
+/// [`PluginGroup`](super::PluginGroup), and tuples over [`Plugins`].
+pub trait Plugins<Marker>: sealed::Plugins<Marker> {}
+
+impl<Marker, T> Plugins<Marker> for T where T: sealed::Plugins<Marker> {}
+
+mod sealed {
+    use alloc::boxed::Box;
+    use variadics_please::all_tuples;
+
+    use crate::{App, AppError, Plugin, PluginGroup};
+
+    pub trait Plugins<Marker> {
+        fn add_to_app(self, app: &mut App);
+    }
+
+    pub struct PluginMarker;
+    pub struct PluginGroupMarker;
+    pub struct PluginsTupleMarker;
+
+    impl<P: Plugin> Plugins<PluginMarker> for P {
+        #[track_caller]
+        fn add_to_app(self, app: &mut App) {
+            if let Err(AppError::DuplicatePlugin {
+                plugin_name,
+            }) = app.add_boxed_plugin(Box::new(self))
+            {
+                panic!("Error adding plugin {plugin_name}: plugin was already added in application")
+            }
+        }
+    }
+
+    impl<P: PluginGroup> Plugins<PluginGroupMarker> for P {
+        #[track_caller]
+        fn add_to_app(self, app: &mut App) {
+            self.build().finish(app);
+        }
+    }
+
+    macro_rules! impl_plugins_tuples {
+        ($(#[$meta:meta])* $(($param: ident, $plugins: ident)),*) => {
+            $(#[$meta])*
+            impl<$($param, $plugins),*> Plugins<(PluginsTupleMarker, $($param,)*)> for ($($plugins,)*)
+            where
+                $($plugins: Plugins<$param>),*
+            {
+                #[expect(
+                    clippy::allow_attributes,
+                    reason = "This is inside a macro, and as such, may not trigger in all cases."
+                )]
+                #[allow(non_snake_case, reason = "`all_tuples!()` generates non-snake-case variable names.")]
+                #[allow(unused_variables, reason = "`app` is unused when implemented for the unit type `()`.")]
+                #[track_caller]
+                fn add_to_app(self, app: &mut App) {
+                    let ($($plugins,)*) = self;
+                    $($plugins.add_to_app(app);)*
+                }
+            }
+        }
+    }
+
+    all_tuples!(
+        #[doc(fake_variadic)]
+        impl_plugins_tuples,
+        0,
+        15,
+        P,
+        S
+    );
+}
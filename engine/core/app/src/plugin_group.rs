@@ -0,0 +1,544 @@
+use crate::{App, AppError, Plugin};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::any::TypeId;
+use obel_platform::collections::TypeIdMap;
+use obel_platform::collections::hash_map::Entry;
+
+/// Combines multiple [`Plugin`]s into a single unit.
+///
+/// If you want an easier, but slightly more restrictive, method of implementing this trait, you
+/// may be interested in composing a [`PluginGroupBuilder`] from within a function:
+///
+/// ```
+/// # use obel_app::{App, Plugin, PluginGroup, PluginGroupBuilder};
+/// # struct PhysicsPlugin;
+/// # impl Plugin for PhysicsPlugin { fn build(&self, _: &mut App) {} }
+/// # struct CombatPlugin;
+/// # impl Plugin for CombatPlugin { fn build(&self, _: &mut App) {} }
+/// struct GameplayPlugins;
+///
+/// impl PluginGroup for GameplayPlugins {
+///     fn build(self) -> PluginGroupBuilder {
+///         PluginGroupBuilder::start::<Self>()
+///             .add(PhysicsPlugin)
+///             .add(CombatPlugin)
+///     }
+/// }
+///
+/// // The server runs the same gameplay without combat.
+/// App::new().add_plugins(GameplayPlugins.build().disable::<CombatPlugin>());
+/// ```
+pub trait PluginGroup: Sized {
+    /// Configures the [`Plugin`]s that are to be added.
+    fn build(self) -> PluginGroupBuilder;
+    /// Configures a name for the [`PluginGroup`] which is primarily used for debugging.
+    fn name() -> String {
+        core::any::type_name::<Self>().to_string()
+    }
+    /// Sets the value of the given [`Plugin`], if it exists
+    fn set<T: Plugin>(self, plugin: T) -> PluginGroupBuilder {
+        self.build().set(plugin)
+    }
+}
+
+struct PluginEntry {
+    plugin: Box<dyn Plugin>,
+    enabled: bool,
+}
+
+impl PluginGroup for PluginGroupBuilder {
+    fn build(self) -> PluginGroupBuilder {
+        self
+    }
+}
+
+/// Facilitates the creation and configuration of a [`PluginGroup`].
+///
+/// Provides a build ordering to ensure that [`Plugin`]s which produce/require a resource
+/// are built before/after dependent/depending [`Plugin`]s. [`Plugin`]s inside the group
+/// can be disabled, enabled or reordered.
+pub struct PluginGroupBuilder {
+    group_name: String,
+    plugins: TypeIdMap<PluginEntry>,
+    order: Vec<TypeId>,
+}
+
+impl PluginGroupBuilder {
+    /// Start a new builder for the [`PluginGroup`].
+    pub fn start<PG: PluginGroup>() -> Self {
+        Self {
+            group_name: PG::name(),
+            plugins: Default::default(),
+            order: Default::default(),
+        }
+    }
+
+    /// Checks if the [`PluginGroupBuilder`] contains the given [`Plugin`].
+    pub fn contains<T: Plugin>(&self) -> bool {
+        self.plugins.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns `true` if the [`PluginGroupBuilder`] contains the given [`Plugin`] and it's enabled.
+    pub fn enabled<T: Plugin>(&self) -> bool {
+        self.plugins.get(&TypeId::of::<T>()).is_some_and(|e| e.enabled)
+    }
+
+    /// Finds the index of a target [`Plugin`].
+    fn index_of<Target: Plugin>(&self) -> Option<usize> {
+        self.order.iter().position(|&ty| ty == TypeId::of::<Target>())
+    }
+
+    // Insert the new plugin as enabled, and removes its previous ordering if it was
+    // already present
+    fn upsert_plugin_state<T: Plugin>(&mut self, plugin: T, added_at_index: usize) {
+        self.upsert_plugin_entry_state(
+            TypeId::of::<T>(),
+            PluginEntry {
+                plugin: Box::new(plugin),
+                enabled: true,
+            },
+            added_at_index,
+        );
+    }
+
+    // Insert the new plugin entry as enabled, and removes its previous ordering if it was
+    // already present
+    fn upsert_plugin_entry_state(
+        &mut self,
+        key: TypeId,
+        plugin: PluginEntry,
+        added_at_index: usize,
+    ) {
+        if let Some(entry) = self.plugins.insert(key, plugin) {
+            if entry.enabled {
+                log::warn!(
+                    "You are replacing plugin '{}' that was not disabled.",
+                    entry.plugin.name()
+                );
+            }
+            if let Some(to_remove) = self
+                .order
+                .iter()
+                .enumerate()
+                .find(|(i, ty)| *i != added_at_index && **ty == key)
+                .map(|(i, _)| i)
+            {
+                self.order.remove(to_remove);
+            }
+        }
+    }
+
+    /// Sets the value of the given [`Plugin`], if it exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Plugin`] does not exist.
+    pub fn set<T: Plugin>(self, plugin: T) -> Self {
+        self.try_set(plugin).unwrap_or_else(|_| {
+            panic!("{} does not exist in this PluginGroup", core::any::type_name::<T>(),)
+        })
+    }
+
+    /// Tries to set the value of the given [`Plugin`], if it exists.
+    ///
+    /// If the given plugin doesn't exist returns self and the passed in [`Plugin`].
+    pub fn try_set<T: Plugin>(mut self, plugin: T) -> Result<Self, (Self, T)> {
+        match self.plugins.entry(TypeId::of::<T>()) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().plugin = Box::new(plugin);
+
+                Ok(self)
+            }
+            Entry::Vacant(_) => Err((self, plugin)),
+        }
+    }
+
+    /// Adds the plugin [`Plugin`] at the end of this [`PluginGroupBuilder`]. If the plugin was
+    /// already in the group, it is removed from its previous place.
+    #[expect(
+        clippy::should_implement_trait,
+        reason = "This does not emulate the `+` operator, but is more akin to pushing to a stack."
+    )]
+    pub fn add<T: Plugin>(mut self, plugin: T) -> Self {
+        let target_index = self.order.len();
+        self.order.push(TypeId::of::<T>());
+        self.upsert_plugin_state(plugin, target_index);
+        self
+    }
+
+    /// Attempts to add the plugin [`Plugin`] at the end of this [`PluginGroupBuilder`].
+    ///
+    /// If the plugin was already in the group the addition fails.
+    pub fn try_add<T: Plugin>(self, plugin: T) -> Result<Self, (Self, T)> {
+        if self.contains::<T>() {
+            return Err((self, plugin));
+        }
+
+        Ok(self.add(plugin))
+    }
+
+    /// Adds a [`PluginGroup`] at the end of this [`PluginGroupBuilder`]. If the plugin was
+    /// already in the group, it is removed from its previous place.
+    pub fn add_group(mut self, group: impl PluginGroup) -> Self {
+        let Self {
+            mut plugins,
+            order,
+            ..
+        } = group.build();
+
+        for plugin_id in order {
+            self.upsert_plugin_entry_state(
+                plugin_id,
+                plugins.remove(&plugin_id).unwrap(),
+                self.order.len(),
+            );
+
+            self.order.push(plugin_id);
+        }
+
+        self
+    }
+
+    /// Adds a [`Plugin`] in this [`PluginGroupBuilder`] before the plugin of type `Target`.
+    /// If the plugin was already the group, it is removed from its previous place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Target` is not already in this [`PluginGroupBuilder`].
+    pub fn add_before<Target: Plugin>(self, plugin: impl Plugin) -> Self {
+        self.try_add_before_overwrite::<Target, _>(plugin).unwrap_or_else(|_| {
+            panic!("Plugin does not exist in group: {}.", core::any::type_name::<Target>())
+        })
+    }
+
+    /// Adds a [`Plugin`] in this [`PluginGroupBuilder`] before the plugin of type `Target`.
+    ///
+    /// If the plugin was already in the group the add fails. If there isn't a plugin
+    /// of type `Target` in the group the plugin we're trying to insert is returned.
+    pub fn try_add_before<Target: Plugin, Insert: Plugin>(
+        self,
+        plugin: Insert,
+    ) -> Result<Self, (Self, Insert)> {
+        if self.contains::<Insert>() {
+            return Err((self, plugin));
+        }
+
+        self.try_add_before_overwrite::<Target, _>(plugin)
+    }
+
+    /// Adds a [`Plugin`] in this [`PluginGroupBuilder`] before the plugin of type `Target`.
+    ///
+    /// If the plugin was already in the group, it is removed from its previous places.
+    /// If there isn't a plugin of type `Target` in the group the plugin we're trying to insert
+    /// is returned.
+    pub fn try_add_before_overwrite<Target: Plugin, Insert: Plugin>(
+        mut self,
+        plugin: Insert,
+    ) -> Result<Self, (Self, Insert)> {
+        let Some(target_index) = self.index_of::<Target>() else {
+            return Err((self, plugin));
+        };
+
+        self.order.insert(target_index, TypeId::of::<Insert>());
+        self.upsert_plugin_state(plugin, target_index);
+        Ok(self)
+    }
+
+    /// Adds a [`Plugin`] in this [`PluginGroupBuilder`] after the plugin of type `Target`.
+    /// If the plugin was already the group, it is removed from its previous place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Target` is not already in this [`PluginGroupBuilder`].
+    pub fn add_after<Target: Plugin>(self, plugin: impl Plugin) -> Self {
+        self.try_add_after_overwrite::<Target, _>(plugin).unwrap_or_else(|_| {
+            panic!("Plugin does not exist in group: {}.", core::any::type_name::<Target>())
+        })
+    }
+
+    /// Adds a [`Plugin`] in this [`PluginGroupBuilder`] after the plugin of type `Target`.
+    ///
+    /// If the plugin was already in the group the add fails. If there isn't a plugin
+    /// of type `Target` in the group the plugin we're trying to insert is returned.
+    pub fn try_add_after<Target: Plugin, Insert: Plugin>(
+        self,
+        plugin: Insert,
+    ) -> Result<Self, (Self, Insert)> {
+        if self.contains::<Insert>() {
+            return Err((self, plugin));
+        }
+
+        self.try_add_after_overwrite::<Target, _>(plugin)
+    }
+
+    /// Adds a [`Plugin`] in this [`PluginGroupBuilder`] after the plugin of type `Target`.
+    ///
+    /// If the plugin was already in the group, it is removed from its previous places.
+    /// If there isn't a plugin of type `Target` in the group the plugin we're trying to insert
+    /// is returned.
+    pub fn try_add_after_overwrite<Target: Plugin, Insert: Plugin>(
+        mut self,
+        plugin: Insert,
+    ) -> Result<Self, (Self, Insert)> {
+        let Some(target_index) = self.index_of::<Target>() else {
+            return Err((self, plugin));
+        };
+
+        let target_index = target_index + 1;
+
+        self.order.insert(target_index, TypeId::of::<Insert>());
+        self.upsert_plugin_state(plugin, target_index);
+        Ok(self)
+    }
+
+    /// Enables a [`Plugin`].
+    ///
+    /// [`Plugin`]s within a [`PluginGroup`] are enabled by default. This function is used to
+    /// opt back in to a [`Plugin`] after [disabling](Self::disable) it. If there are no plugins
+    /// of type `T` in this group, it will panic.
+    pub fn enable<T: Plugin>(mut self) -> Self {
+        let plugin_entry = self
+            .plugins
+            .get_mut(&TypeId::of::<T>())
+            .expect("Cannot enable a plugin that does not exist.");
+        plugin_entry.enabled = true;
+        self
+    }
+
+    /// Disables a [`Plugin`], preventing it from being added to the [`App`] with the rest of the
+    /// [`PluginGroup`]. The disabled [`Plugin`] keeps its place in the [`PluginGroup`], so it can
+    /// still be used for ordering with [`add_before`](Self::add_before) or
+    /// [`add_after`](Self::add_after), or it can be [re-enabled](Self::enable). If there are no
+    /// plugins of type `T` in this group, it will panic.
+    pub fn disable<T: Plugin>(mut self) -> Self {
+        let plugin_entry = self
+            .plugins
+            .get_mut(&TypeId::of::<T>())
+            .expect("Cannot disable a plugin that does not exist.");
+        plugin_entry.enabled = false;
+        self
+    }
+
+    /// Consumes the [`PluginGroupBuilder`] and [builds](Plugin::build) the contained [`Plugin`]s
+    /// in the order specified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the plugin in the group was already added to the application.
+    #[track_caller]
+    pub fn finish(mut self, app: &mut App) {
+        for ty in &self.order {
+            if let Some(entry) = self.plugins.remove(ty) {
+                if entry.enabled {
+                    log::debug!("added plugin: {}", entry.plugin.name());
+                    if let Err(AppError::DuplicatePlugin {
+                        plugin_name,
+                    }) = app.add_boxed_plugin(entry.plugin)
+                    {
+                        panic!(
+                            "Error adding plugin {} in group {}: plugin was already added in application",
+                            plugin_name, self.group_name
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A plugin group which doesn't do anything. Useful for examples:
+/// ```
+/// # use obel_app::prelude::*;
+/// use obel_app::NoopPluginGroup as MinimalPlugins;
+///
+/// fn main(){
+///     App::new().add_plugins(MinimalPlugins).run();
+/// }
+/// ```
+#[doc(hidden)]
+pub struct NoopPluginGroup;
+
+impl PluginGroup for NoopPluginGroup {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::{any::TypeId, fmt::Debug};
+
+    use super::PluginGroupBuilder;
+    use crate::{App, NoopPluginGroup, Plugin};
+
+    struct PluginA;
+    impl Plugin for PluginA {
+        fn build(&self, _: &mut App) {}
+    }
+
+    struct PluginB;
+    impl Plugin for PluginB {
+        fn build(&self, _: &mut App) {}
+    }
+
+    struct PluginC;
+    impl Plugin for PluginC {
+        fn build(&self, _: &mut App) {}
+    }
+
+    #[derive(PartialEq, Debug)]
+    struct PluginWithData(u32);
+    impl Plugin for PluginWithData {
+        fn build(&self, _: &mut App) {}
+    }
+
+    fn get_plugin<T: Debug + 'static>(group: &PluginGroupBuilder, id: TypeId) -> &T {
+        group.plugins[&id].plugin.as_any().downcast_ref::<T>().unwrap()
+    }
+
+    #[test]
+    fn contains() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>().add(PluginA).add(PluginB);
+
+        assert!(group.contains::<PluginA>());
+        assert!(!group.contains::<PluginC>());
+
+        let group = group.disable::<PluginA>();
+
+        assert!(group.enabled::<PluginB>());
+        assert!(!group.enabled::<PluginA>());
+    }
+
+    #[test]
+    fn basic_ordering() {
+        let group =
+            PluginGroupBuilder::start::<NoopPluginGroup>().add(PluginA).add(PluginB).add(PluginC);
+
+        assert_eq!(
+            group.order,
+            vec![TypeId::of::<PluginA>(), TypeId::of::<PluginB>(), TypeId::of::<PluginC>(),]
+        );
+    }
+
+    #[test]
+    fn add_before() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(PluginA)
+            .add(PluginB)
+            .add_before::<PluginB>(PluginC);
+
+        assert_eq!(
+            group.order,
+            vec![TypeId::of::<PluginA>(), TypeId::of::<PluginC>(), TypeId::of::<PluginB>(),]
+        );
+    }
+
+    #[test]
+    fn try_add_before() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>().add(PluginA);
+
+        let Ok(group) = group.try_add_before::<PluginA, _>(PluginC) else {
+            panic!("PluginA wasn't in group");
+        };
+
+        assert_eq!(group.order, vec![TypeId::of::<PluginC>(), TypeId::of::<PluginA>(),]);
+
+        assert!(group.try_add_before::<PluginA, _>(PluginC).is_err());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Plugin does not exist in group: obel_app::plugin_group::tests::PluginB."
+    )]
+    fn add_before_nonexistent() {
+        PluginGroupBuilder::start::<NoopPluginGroup>().add(PluginA).add_before::<PluginB>(PluginC);
+    }
+
+    #[test]
+    fn add_after() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(PluginA)
+            .add(PluginB)
+            .add_after::<PluginA>(PluginC);
+
+        assert_eq!(
+            group.order,
+            vec![TypeId::of::<PluginA>(), TypeId::of::<PluginC>(), TypeId::of::<PluginB>(),]
+        );
+    }
+
+    #[test]
+    fn try_add_after() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>().add(PluginA).add(PluginB);
+
+        let Ok(group) = group.try_add_after::<PluginA, _>(PluginC) else {
+            panic!("PluginA wasn't in group");
+        };
+
+        assert_eq!(
+            group.order,
+            vec![TypeId::of::<PluginA>(), TypeId::of::<PluginC>(), TypeId::of::<PluginB>(),]
+        );
+
+        assert!(group.try_add_after::<PluginA, _>(PluginC).is_err());
+    }
+
+    #[test]
+    fn readd() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(PluginA)
+            .add(PluginB)
+            .add(PluginC)
+            .add(PluginB);
+
+        assert_eq!(
+            group.order,
+            vec![TypeId::of::<PluginA>(), TypeId::of::<PluginC>(), TypeId::of::<PluginB>(),]
+        );
+    }
+
+    #[test]
+    fn set_replaces_plugin_value() {
+        let group = PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(PluginWithData(0x0F))
+            .add(PluginC)
+            .set(PluginWithData(0xA0));
+
+        assert_eq!(
+            get_plugin::<PluginWithData>(&group, TypeId::of::<PluginWithData>()),
+            &PluginWithData(0xA0)
+        );
+        assert_eq!(group.order, vec![TypeId::of::<PluginWithData>(), TypeId::of::<PluginC>(),]);
+    }
+
+    #[test]
+    fn add_basic_subgroup() {
+        let group_a = PluginGroupBuilder::start::<NoopPluginGroup>().add(PluginA).add(PluginB);
+
+        let group_b =
+            PluginGroupBuilder::start::<NoopPluginGroup>().add_group(group_a).add(PluginC);
+
+        assert_eq!(
+            group_b.order,
+            vec![TypeId::of::<PluginA>(), TypeId::of::<PluginB>(), TypeId::of::<PluginC>(),]
+        );
+    }
+
+    #[test]
+    fn disabled_plugins_are_not_added() {
+        let mut app = App::new();
+        PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(PluginA)
+            .add(PluginB)
+            .disable::<PluginB>()
+            .finish(&mut app);
+
+        assert!(app.is_plugin_added::<PluginA>());
+        assert!(!app.is_plugin_added::<PluginB>());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{App, AppExit, Plugin, PluginsState};
+use core::time::Duration;
+use obel_platform::time::Instant;
+
+/// Determines the method used to run an [`App`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RunMode {
+    /// Indicates that the [`App`]'s update loop should run repeatedly until an [`AppExit`] is
+    /// requested.
+    Loop {
+        /// The [`Duration`] between the starts of two consecutive updates.
+        /// When `None`, the app will loop as fast as possible.
+        wait: Option<Duration>,
+    },
+    /// Indicates that the [`App`]'s update loop should run only once.
+    Once,
+}
+
+impl Default for RunMode {
+    fn default() -> Self {
+        RunMode::Loop {
+            wait: None,
+        }
+    }
+}
+
+/// Configures an [`App`] to run its update loop without a window or event loop.
+///
+/// This is the runner used by tests and headless servers:
+///
+/// - [`ScheduleRunnerPlugin::run_once`] runs a single update, which is what most tests want.
+/// - [`ScheduleRunnerPlugin::run_loop`] runs updates until an [`AppExit`] is requested,
+///   starting a new frame every `wait`.
+/// - [`ScheduleRunnerPlugin::run_at_tick_rate`] is a loop targeting a fixed number of updates per
+///   second, e.g. the 60 Hz command frames of a game server.
+///
+/// Frames are scheduled against absolute deadlines, so a frame running late is made up by
+/// the following ones instead of shifting every later frame. If the app falls more than
+/// [`MAX_FRAMES_BEHIND`] frames behind, e.g. after a long stall, the schedule restarts from the
+/// current time rather than running a burst of catch-up frames.
+///
+/// The loop exits gracefully: the frame in progress completes, then the runner returns the
+/// requested [`AppExit`]. An exit can be requested by the app itself with [`App::exit`], or
+/// from another thread (e.g. a signal handler or an admin console) through an
+/// [`AppExitSignal`](crate::AppExitSignal) obtained from [`App::exit_signal`].
+///
+/// ```no_run
+/// # use obel_app::{App, AppExit, ScheduleRunnerPlugin};
+/// let mut app = App::new();
+/// app.add_plugins(ScheduleRunnerPlugin::run_at_tick_rate(60.0));
+///
+/// let signal = app.exit_signal();
+/// std::thread::spawn(move || {
+///     // e.g. wait for a shutdown request, then:
+///     signal.exit(AppExit::Success);
+/// });
+///
+/// assert_eq!(app.run(), AppExit::Success);
+/// ```
+#[derive(Default)]
+pub struct ScheduleRunnerPlugin {
+    /// Determines whether the app runs its update loop once or repeatedly.
+    pub run_mode: RunMode,
+}
+
+impl ScheduleRunnerPlugin {
+    /// See [`RunMode::Once`].
+    pub fn run_once() -> Self {
+        ScheduleRunnerPlugin {
+            run_mode: RunMode::Once,
+        }
+    }
+
+    /// See [`RunMode::Loop`].
+    pub fn run_loop(wait_duration: Duration) -> Self {
+        ScheduleRunnerPlugin {
+            run_mode: RunMode::Loop {
+                wait: Some(wait_duration),
+            },
+        }
+    }
+
+    /// A [`RunMode::Loop`] that runs `ticks_per_second` updates per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ticks_per_second` is not strictly positive and finite.
+    pub fn run_at_tick_rate(ticks_per_second: f64) -> Self {
+        assert!(
+            ticks_per_second.is_finite() && ticks_per_second > 0.0,
+            "tick rate must be strictly positive and finite, got {ticks_per_second}"
+        );
+        Self::run_loop(Duration::from_secs_f64(1.0 / ticks_per_second))
+    }
+}
+
+impl Plugin for ScheduleRunnerPlugin {
+    fn build(&self, app: &mut App) {
+        let run_mode = self.run_mode;
+        app.set_runner(move |mut app: App| {
+            let plugins_state = app.plugins_state();
+            if plugins_state != PluginsState::Cleaned {
+                while app.plugins_state() == PluginsState::Adding {
+                    crate::task_pool_plugin::tick_global_task_pools();
+                }
+                app.finish();
+                app.cleanup();
+            }
+
+            match run_mode {
+                RunMode::Once => {
+                    app.update();
+                    app.should_exit().unwrap_or(AppExit::Success)
+                }
+                RunMode::Loop {
+                    wait,
+                } => {
+                    let mut deadline = Instant::now();
+                    loop {
+                        app.update();
+
+                        if let Some(exit) = app.should_exit() {
+                            break exit;
+                        }
+
+                        if let Some(wait) = wait {
+                            let now = Instant::now();
+                            deadline = next_deadline(deadline, now, wait);
+                            if deadline > now {
+                                sleep(deadline - now);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// The number of frames a [`RunMode::Loop`] may fall behind its schedule before it stops
+/// catching up and restarts its schedule from the current time.
+pub const MAX_FRAMES_BEHIND: u32 = 4;
+
+/// Returns the start of the frame following the one scheduled at `deadline`.
+fn next_deadline(deadline: Instant, now: Instant, wait: Duration) -> Instant {
+    let next = deadline + wait;
+    if now.saturating_duration_since(next) > wait * MAX_FRAMES_BEHIND {
+        now
+    } else {
+        next
+    }
+}
+
+/// Blocks the current thread for `duration`.
+///
+/// Without `std` there is no way to park the thread, so this spins on [`Instant`] instead.
+fn sleep(duration: Duration) {
+    #[cfg(feature = "std")]
+    std::thread::sleep(duration);
+
+    #[cfg(not(feature = "std"))]
+    {
+        let start = Instant::now();
+        while start.elapsed() < duration {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{MAX_FRAMES_BEHIND, ScheduleRunnerPlugin, next_deadline};
+    use crate::{App, AppExit};
+    use core::{num::NonZero, time::Duration};
+    use obel_platform::time::Instant;
+
+    #[test]
+    fn run_once_exits_successfully() {
+        let mut app = App::new();
+        app.add_plugins(ScheduleRunnerPlugin::run_once());
+        assert_eq!(app.run(), AppExit::Success);
+    }
+
+    #[test]
+    fn loop_exits_when_requested_before_run() {
+        let mut app = App::new();
+        app.add_plugins(ScheduleRunnerPlugin::run_at_tick_rate(60.0));
+        app.exit_with(AppExit::from_code(3));
+        assert_eq!(app.run(), AppExit::Error(NonZero::new(3).unwrap()));
+    }
+
+    #[test]
+    fn loop_exits_on_signal_from_another_thread() {
+        let mut app = App::new();
+        app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_millis(5)));
+
+        let signal = app.exit_signal();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            signal.exit(AppExit::Success);
+        });
+
+        let start = Instant::now();
+        assert_eq!(app.run(), AppExit::Success);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn deadlines_do_not_accumulate_overshoot() {
+        let wait = Duration::from_millis(10);
+        let start = Instant::now();
+        // every frame wakes up 1ms late, the schedule stays on multiples of `wait`
+        let mut deadline = start;
+        for frame in 1..=10 {
+            let now = deadline + Duration::from_millis(1);
+            deadline = next_deadline(deadline, now, wait);
+            assert_eq!(deadline, start + wait * frame);
+        }
+    }
+
+    #[test]
+    fn deadline_reanchors_after_large_overrun() {
+        let wait = Duration::from_millis(10);
+        let start = Instant::now();
+
+        // a few frames behind: catch up
+        let now = start + wait * MAX_FRAMES_BEHIND;
+        assert_eq!(next_deadline(start, now, wait), start + wait);
+
+        // too far behind: restart from now
+        let now = start + wait * (MAX_FRAMES_BEHIND + 2);
+        assert_eq!(next_deadline(start, now, wait), now);
+    }
+
+    #[test]
+    #[should_panic(expected = "tick rate must be strictly positive and finite")]
+    fn zero_tick_rate_panics() {
+        ScheduleRunnerPlugin::run_at_tick_rate(0.0);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{App, Plugin};
+use obel_tasks::{AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool, TaskPool};
+
+/// Setup of default task pools: [`AsyncComputeTaskPool`], [`ComputeTaskPool`], [`IoTaskPool`].
+///
+/// Once the pools are initialized, [`App::update`] ticks their local executors on the thread
+/// running the app, see [`obel_tasks::tick_global_task_pools_on_main_thread`].
+#[derive(Default)]
+pub struct TaskPoolPlugin;
+
+impl Plugin for TaskPoolPlugin {
+    fn build(&self, _app: &mut App) {
+        ComputeTaskPool::get_or_init(TaskPool::new);
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+        IoTaskPool::get_or_init(TaskPool::new);
+    }
+}
+
+/// Ticks the local executors of the global task pools, if all of them have been initialized.
+pub(crate) fn tick_global_task_pools() {
+    #[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+    if ComputeTaskPool::try_get().is_some()
+        && AsyncComputeTaskPool::try_get().is_some()
+        && IoTaskPool::try_get().is_some()
+    {
+        obel_tasks::tick_global_task_pools_on_main_thread();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TaskPoolPlugin;
+    use crate::App;
+    use obel_tasks::{AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool};
+
+    #[test]
+    fn initializes_global_task_pools() {
+        let mut app = App::new();
+        app.add_plugins(TaskPoolPlugin);
+        assert!(ComputeTaskPool::try_get().is_some());
+        assert!(AsyncComputeTaskPool::try_get().is_some());
+        assert!(IoTaskPool::try_get().is_some());
+
+        // ticking the initialized pools must not panic
+        app.update();
+    }
+}
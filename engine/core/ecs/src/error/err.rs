@@ -1,4 +1,4 @@
-use alloc::boxed::Box;
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
 use core::{
     error::Error,
     fmt::{Debug, Display},
@@ -7,12 +7,23 @@ use core::{
 /// The built in "universal" Obel error type. This has a blanket [`From`] impl for any type that implements Rust's [`Error`],
 /// meaning it can be used as a "catch all" error.
 ///
+/// # Error codes and context
+///
+/// Every error carries an [`ErrorCode`] (defaulting to [`ErrorCode::Unknown`]) and a chain of [`ContextFrame`]s
+/// describing where it happened: the system that failed, the entity and component it was working on, the tick, etc.
+/// Both can be attached with the builder-style `with_*` methods and are printed along with the error message.
+///
 /// # Backtraces
 ///
 /// When used with the `backtrace` Cargo feature, it will capture a backtrace when the error is constructed (generally in the [`From`] impl]).
 /// When printed, the backtrace will be displayed. By default, the backtrace will be trimmed down to filter out noise. To see the full backtrace,
 /// set the `OBEL__BACKTRACE=full` environment variable.
 ///
+/// # Serialization
+///
+/// With the `serialize` feature, [`ObelError::report`] produces an [`ErrorReport`] which can be exported
+/// through any `serde` format (e.g. JSON), so errors can be stored alongside recorded frames.
+///
 /// # Usage
 ///
 /// ```ignore
@@ -25,6 +36,16 @@ use core::{
 ///     Ok(())
 /// }
 /// ```
+///
+/// ```
+/// # use obel_ecs::error::{ErrorCode, ObelError};
+/// let error = ObelError::from("the unit has no target")
+///     .with_code(ErrorCode::InvalidState)
+///     .with_system("ai::pick_target")
+///     .with_tick(42);
+/// assert_eq!(error.code(), ErrorCode::InvalidState);
+/// assert_eq!(error.context().len(), 2);
+/// ```
 pub struct ObelError {
     inner: Box<InnerObelError>,
 }
@@ -34,6 +55,94 @@ impl ObelError {
     pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
         self.inner.error.downcast_ref::<E>()
     }
+
+    /// Returns the wrapped error.
+    pub fn inner(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.inner.error
+    }
+
+    /// Returns the [`ErrorCode`] of this error.
+    pub fn code(&self) -> ErrorCode {
+        self.inner.code
+    }
+
+    /// Returns the context chain of this error, in the order it was attached.
+    pub fn context(&self) -> &[ContextFrame] {
+        &self.inner.context
+    }
+
+    /// Returns the lower-level source of the wrapped error, if any.
+    ///
+    /// This mirrors [`Error::source`], which [`ObelError`] cannot implement without
+    /// conflicting with its blanket [`From`] impl.
+    pub fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner.error.source()
+    }
+
+    /// Returns an iterator over the wrapped error followed by each of its [`Error::source`]s.
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn Error + 'static)> {
+        let mut next: Option<&(dyn Error + 'static)> = Some(&*self.inner.error);
+        core::iter::from_fn(move || {
+            let current = next?;
+            next = current.source();
+            Some(current)
+        })
+    }
+
+    /// Returns the last error of the [`chain`](Self::chain), i.e. the error that originally caused this one.
+    pub fn root_cause(&self) -> &(dyn Error + 'static) {
+        // The chain always contains at least the wrapped error.
+        self.chain().last().unwrap_or(&*self.inner.error)
+    }
+
+    /// Sets the [`ErrorCode`] of this error.
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.inner.code = code;
+        self
+    }
+
+    /// Attaches a [`ContextFrame`] to this error.
+    pub fn with_context(mut self, frame: ContextFrame) -> Self {
+        self.inner.context.push(frame);
+        self
+    }
+
+    /// Attaches the name of the system that produced this error.
+    pub fn with_system(self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.with_context(ContextFrame::System(name.into()))
+    }
+
+    /// Attaches the entity this error relates to, using the entity's raw `u64` bits.
+    pub fn with_entity(self, bits: u64) -> Self {
+        self.with_context(ContextFrame::Entity(bits))
+    }
+
+    /// Attaches the component `C` this error relates to.
+    pub fn with_component<C: ?Sized + 'static>(self) -> Self {
+        self.with_context(ContextFrame::Component(Cow::Borrowed(core::any::type_name::<C>())))
+    }
+
+    /// Attaches the world tick at which this error happened.
+    pub fn with_tick(self, tick: u32) -> Self {
+        self.with_context(ContextFrame::Tick(tick))
+    }
+
+    /// Attaches a free-form message to this error.
+    pub fn with_message(self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.with_context(ContextFrame::Message(message.into()))
+    }
+
+    /// Captures the code, message, context chain and sources of this error in a plain,
+    /// serializable [`ErrorReport`].
+    pub fn report(&self) -> ErrorReport {
+        let mut chain = self.chain().map(alloc::string::ToString::to_string);
+        ErrorReport {
+            code: self.code(),
+            message: chain.next().unwrap_or_default(),
+            context: self.inner.context.clone(),
+            sources: chain.collect(),
+        }
+    }
 }
 
 /// This type exists (rather than having a `ObelError(Box<dyn InnerObelError)`) to make [`ObelError`] use a "thin pointer" instead of
@@ -43,10 +152,122 @@ impl ObelError {
 /// of the current impl is nice.
 struct InnerObelError {
     error: Box<dyn Error + Send + Sync + 'static>,
+    code: ErrorCode,
+    context: Vec<ContextFrame>,
     #[cfg(feature = "backtrace")]
     backtrace: std::backtrace::Backtrace,
 }
 
+/// A typed, stable classification of an [`ObelError`].
+///
+/// Codes are meant for tooling: filtering logs, aggregating failures across recorded frames
+/// or matching on errors without downcasting. The numeric value returned by [`ErrorCode::as_u64`]
+/// is stable across releases, and distinct for distinct codes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorCode {
+    /// The error has not been classified.
+    #[default]
+    Unknown,
+    /// An entity did not exist, or was despawned.
+    EntityNotFound,
+    /// A required component was missing from an entity.
+    ComponentNotFound,
+    /// A required resource was missing from the world.
+    ResourceNotFound,
+    /// A system parameter could not be fetched or failed validation.
+    InvalidParameter,
+    /// The world or a piece of data was in an unexpected state.
+    InvalidState,
+    /// A command failed to apply.
+    CommandFailed,
+    /// Data could not be parsed or deserialized.
+    Parse,
+    /// An application-defined code.
+    Custom(u32),
+}
+
+impl ErrorCode {
+    /// The bit set in the numeric value of every [`ErrorCode::Custom`] code.
+    ///
+    /// Built-in codes use the values below `2^32`, and custom codes the values
+    /// `CUSTOM_TAG | code`, so that adding built-in codes never changes nor collides with an
+    /// existing custom code.
+    pub const CUSTOM_TAG: u64 = 1 << 32;
+
+    /// Returns the stable numeric value of this code.
+    ///
+    /// Distinct codes have distinct values, see [`ErrorCode::CUSTOM_TAG`].
+    pub const fn as_u64(self) -> u64 {
+        match self {
+            Self::Unknown => 0,
+            Self::EntityNotFound => 1,
+            Self::ComponentNotFound => 2,
+            Self::ResourceNotFound => 3,
+            Self::InvalidParameter => 4,
+            Self::InvalidState => 5,
+            Self::CommandFailed => 6,
+            Self::Parse => 7,
+            Self::Custom(code) => Self::CUSTOM_TAG | code as u64,
+        }
+    }
+}
+
+/// Built-in codes are displayed as `E0007`, custom codes as `C0007`.
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Custom(code) => write!(f, "C{code:04}"),
+            code => write!(f, "E{:04}", code.as_u64()),
+        }
+    }
+}
+
+/// A single piece of context attached to an [`ObelError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContextFrame {
+    /// The name of the system that was running.
+    System(Cow<'static, str>),
+    /// The raw `u64` bits of the entity involved.
+    Entity(u64),
+    /// The type name of the component involved.
+    Component(Cow<'static, str>),
+    /// The world tick at which the error happened.
+    Tick(u32),
+    /// A free-form message.
+    Message(Cow<'static, str>),
+}
+
+impl Display for ContextFrame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::System(name) => write!(f, "in system `{name}`"),
+            Self::Entity(bits) => write!(f, "on entity {bits:#x}"),
+            Self::Component(name) => write!(f, "with component `{name}`"),
+            Self::Tick(tick) => write!(f, "at tick {tick}"),
+            Self::Message(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// A plain snapshot of an [`ObelError`], created by [`ObelError::report`].
+///
+/// Unlike [`ObelError`] itself, this can be cloned, compared and (with the `serialize` feature)
+/// exported to any `serde` format, e.g. for storing per-frame error logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorReport {
+    /// The code of the error.
+    pub code: ErrorCode,
+    /// The message of the wrapped error.
+    pub message: String,
+    /// The context chain of the error, in the order it was attached.
+    pub context: Vec<ContextFrame>,
+    /// The messages of the [`Error::source`] chain of the wrapped error.
+    pub sources: Vec<String>,
+}
+
 // NOTE: writing the impl this way gives us From<&str> ... nice!
 impl<E> From<E> for ObelError
 where
@@ -57,6 +278,8 @@ where
         ObelError {
             inner: Box::new(InnerObelError {
                 error: error.into(),
+                code: ErrorCode::default(),
+                context: Vec::new(),
                 #[cfg(feature = "backtrace")]
                 backtrace: std::backtrace::Backtrace::capture(),
             }),
@@ -66,7 +289,13 @@ where
 
 impl Display for ObelError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.inner.code != ErrorCode::Unknown {
+            write!(f, "[{}] ", self.inner.code)?;
+        }
         writeln!(f, "{}", self.inner.error)?;
+        for frame in &self.inner.context {
+            writeln!(f, "    {frame}")?;
+        }
         Ok(())
     }
 }
@@ -74,6 +303,12 @@ impl Display for ObelError {
 impl Debug for ObelError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "{:?}", self.inner.error)?;
+        if self.inner.code != ErrorCode::Unknown {
+            writeln!(f, "    code: {}", self.inner.code)?;
+        }
+        for frame in &self.inner.context {
+            writeln!(f, "    {frame}")?;
+        }
         #[cfg(feature = "backtrace")]
         {
             let backtrace = &self.inner.backtrace;
@@ -148,7 +383,7 @@ pub fn obel_error_panic_hook(
         if SKIP_NORMAL_BACKTRACE.replace(false) {
             if let Some(payload) = info.payload().downcast_ref::<&str>() {
                 std::println!("{payload}");
-            } else if let Some(payload) = info.payload().downcast_ref::<alloc::string::String>() {
+            } else if let Some(payload) = info.payload().downcast_ref::<String>() {
                 std::println!("{payload}");
             }
             return;
@@ -161,6 +396,136 @@ pub fn obel_error_panic_hook(
 // @TODO: refactor error handling using thiserror/sutf library and refactor this test
 #[cfg(test)]
 mod tests {
+    use super::{ContextFrame, ErrorCode, ObelError};
+    use alloc::{format, string::ToString, vec};
+    use core::{error::Error, fmt};
+
+    #[derive(Debug)]
+    struct Outer(Inner);
+
+    #[derive(Debug)]
+    struct Inner;
+
+    impl fmt::Display for Outer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("failed to spawn projectile")
+        }
+    }
+
+    impl fmt::Display for Inner {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("prefab not loaded")
+        }
+    }
+
+    impl Error for Outer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    impl Error for Inner {}
+
+    struct Health;
+
+    #[test]
+    fn code_and_context() {
+        let error = ObelError::from("out of mana")
+            .with_code(ErrorCode::InvalidState)
+            .with_system("cast_spell")
+            .with_entity(7)
+            .with_component::<Health>()
+            .with_tick(3);
+
+        assert_eq!(error.code(), ErrorCode::InvalidState);
+        assert_eq!(
+            error.context(),
+            &[
+                ContextFrame::System("cast_spell".into()),
+                ContextFrame::Entity(7),
+                ContextFrame::Component(core::any::type_name::<Health>().into()),
+                ContextFrame::Tick(3),
+            ]
+        );
+
+        let message = error.to_string();
+        assert!(message.starts_with("[E0005] out of mana\n"));
+        assert!(message.contains("in system `cast_spell`"));
+        assert!(message.contains("at tick 3"));
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(ObelError::from("unclassified").code(), ErrorCode::Unknown);
+        assert_eq!(ErrorCode::Parse.as_u64(), 7);
+        assert_eq!(ErrorCode::Parse.to_string(), "E0007");
+        assert_eq!(ErrorCode::Custom(2).as_u64(), ErrorCode::CUSTOM_TAG | 2);
+        assert_eq!(ErrorCode::Custom(2).to_string(), "C0002");
+
+        let codes = [
+            ErrorCode::Unknown,
+            ErrorCode::EntityNotFound,
+            ErrorCode::ComponentNotFound,
+            ErrorCode::ResourceNotFound,
+            ErrorCode::InvalidParameter,
+            ErrorCode::InvalidState,
+            ErrorCode::CommandFailed,
+            ErrorCode::Parse,
+            ErrorCode::Custom(0),
+            ErrorCode::Custom(7),
+            ErrorCode::Custom(1000),
+            ErrorCode::Custom(u32::MAX - 1),
+            ErrorCode::Custom(u32::MAX),
+        ];
+        for code in &codes[..8] {
+            assert!(code.as_u64() < ErrorCode::CUSTOM_TAG);
+        }
+        for (i, a) in codes.iter().enumerate() {
+            for b in &codes[i + 1..] {
+                assert_ne!(a.as_u64(), b.as_u64(), "{a:?} and {b:?} share a numeric code");
+            }
+        }
+    }
+
+    #[test]
+    fn source_chain() {
+        let error = ObelError::from(Outer(Inner));
+        assert_eq!(error.inner().to_string(), "failed to spawn projectile");
+        assert_eq!(error.source().unwrap().to_string(), "prefab not loaded");
+        assert_eq!(error.chain().count(), 2);
+        assert!(error.root_cause().downcast_ref::<Inner>().is_some());
+
+        let error = ObelError::from("no sources");
+        assert!(error.source().is_none());
+        assert_eq!(error.root_cause().to_string(), "no sources");
+    }
+
+    #[test]
+    fn report() {
+        let report = ObelError::from(Outer(Inner))
+            .with_code(ErrorCode::Custom(1))
+            .with_message("while spawning a wave")
+            .report();
+        assert_eq!(report.code, ErrorCode::Custom(1));
+        assert_eq!(report.message, "failed to spawn projectile");
+        assert_eq!(report.context, vec![ContextFrame::Message("while spawning a wave".into())]);
+        assert_eq!(report.sources, vec!["prefab not loaded"]);
+        assert_eq!(format!("{:?}", report.code), "Custom(1)");
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn report_to_json() {
+        let report =
+            ObelError::from("desync").with_code(ErrorCode::InvalidState).with_tick(9).report();
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            r#"{"code":"InvalidState","message":"desync","context":[{"Tick":9}],"sources":[]}"#
+        );
+        let deserialized: super::ErrorReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, report);
+    }
 
     #[test]
     #[cfg(not(miri))] // miri backtraces are weird
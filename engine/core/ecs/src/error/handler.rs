@@ -107,14 +107,21 @@ impl ErrorContext {
     }
 }
 
+/// The signature shared by all error handlers.
+///
+/// Any function (or non-capturing closure) matching it can be used wherever Obel expects an
+/// error handler, including [`set_global_default_error_handler`].
+pub type ErrorHandler = fn(ObelError, ErrorContext);
+
 /// A global error handler. This can be set at startup, as long as it is set before
 /// any uses. This should generally be configured _before_ initializing the app.
 ///
 /// This should be set inside of your `main` function, before initializing the Obel app.
 /// The value of this error handler can be accessed using the [`default_error_handler`] function,
 /// which calls [`OnceLock::get_or_init`] to get the value.
+/// Prefer [`set_global_default_error_handler`] over setting it directly.
 ///
-/// **Note:** this is only available when the `configurable_error_handler` feature of `obel_ecs` (or `bevy`) is enabled!
+/// **Note:** this is only available when the `configurable_error_handler` feature of `obel_ecs` (or `obel`) is enabled!
 ///
 /// # Example
 ///
@@ -139,14 +146,34 @@ impl ErrorContext {
 ///
 /// As this can *never* be overwritten, library code should never set this value.
 #[cfg(feature = "configurable_error_handler")]
-pub static GLOBAL_ERROR_HANDLER: OnceLock<fn(ObelError, ErrorContext)> = OnceLock::new();
+pub static GLOBAL_ERROR_HANDLER: OnceLock<ErrorHandler> = OnceLock::new();
+
+/// Sets the [`GLOBAL_ERROR_HANDLER`] used by [`default_error_handler`].
+///
+/// The handler can only be set once, and only before the default error handler has been read
+/// for the first time. If the handler was already initialized, the rejected `handler` is
+/// returned in the `Err` variant.
+///
+/// **Note:** this is only available when the `configurable_error_handler` feature of `obel_ecs` (or `obel`) is enabled!
+///
+/// # Example
+///
+/// ```
+/// # use obel_ecs::error::{set_global_default_error_handler, warn};
+/// set_global_default_error_handler(warn).expect("The error handler can only be set once, globally.");
+/// // initialize Obel App here
+/// ```
+#[cfg(feature = "configurable_error_handler")]
+pub fn set_global_default_error_handler(handler: ErrorHandler) -> Result<(), ErrorHandler> {
+    GLOBAL_ERROR_HANDLER.set(handler)
+}
 
 /// The default error handler. This defaults to [`panic()`],
 /// but if set, the [`GLOBAL_ERROR_HANDLER`] will be used instead, enabling error handler customization.
 /// The `configurable_error_handler` feature must be enabled to change this from the panicking default behavior,
 /// as there may be runtime overhead.
 #[inline]
-pub fn default_error_handler() -> fn(ObelError, ErrorContext) {
+pub fn default_error_handler() -> ErrorHandler {
     #[cfg(not(feature = "configurable_error_handler"))]
     return panic;
 
@@ -206,3 +233,58 @@ pub fn trace(error: ObelError, ctx: ErrorContext) {
 #[track_caller]
 #[inline]
 pub fn ignore(_: ObelError, _: ErrorContext) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> ErrorContext {
+        ErrorContext::System {
+            name: "my_system".into(),
+            last_run: 0,
+        }
+    }
+
+    #[test]
+    fn context_name_and_kind() {
+        let ctx = ctx();
+        assert_eq!(ctx.name(), "my_system");
+        assert_eq!(ctx.kind(), "system");
+        assert_eq!(alloc::format!("{ctx}"), "System `my_system` failed");
+
+        let ctx = ErrorContext::Command {
+            name: "my_command".into(),
+        };
+        assert_eq!(ctx.kind(), "command");
+        assert_eq!(alloc::format!("{ctx}"), "Command `my_command` failed");
+    }
+
+    #[test]
+    fn logging_handlers_do_not_panic() {
+        let handlers: [ErrorHandler; 6] = [error, warn, info, debug, trace, ignore];
+        for handler in handlers {
+            handler("boom".into(), ctx());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Encountered an error in system `my_system`")]
+    fn panic_handler_panics() {
+        panic("boom".into(), ctx());
+    }
+
+    #[test]
+    #[cfg(feature = "configurable_error_handler")]
+    fn global_error_handler_can_only_be_set_once() {
+        // Either this test sets the handler, or it has already been initialized elsewhere.
+        let _ = set_global_default_error_handler(ignore);
+        assert!(set_global_default_error_handler(warn).is_err());
+    }
+
+    #[test]
+    #[cfg(not(feature = "configurable_error_handler"))]
+    #[should_panic]
+    fn default_error_handler_panics() {
+        default_error_handler()("boom".into(), ctx());
+    }
+}
@@ -1,18 +1,13 @@
 //! Error handling for "fallible" systems.
 //!
-//! When a system is added to a [`Schedule`], and its return type is that of [`Result`], then Obel
-//! considers those systems to be "fallible", and the ECS scheduler will special-case the [`Err`]
-//! variant of the returned `Result`.
+//! Systems, commands and observers returning a [`Result`] are considered "fallible": their
+//! [`ObelError`]s are passed to an "error handler" along with an [`ErrorContext`] describing
+//! where the error occurred. By default, the [`panic`] error handler function is used, resulting
+//! in a panic with the error message attached.
 //!
-//! All [`ObelError`]s returned by a system are handled by an "error handler". By default, the
-//! [`panic`] error handler function is used, resulting in a panic with the error message attached.
-//!
-//! You can change the default behavior by registering a custom error handler, either globally or
-//! per `Schedule`:
-//!
-//! - [`App::set_system_error_handler`] sets the global error handler for all systems of the
-//!   current [`World`].
-//! - [`Schedule::set_error_handler`] sets the error handler for all systems of that schedule.
+//! The default can be changed for the whole process with `set_global_default_error_handler`,
+//! which requires the `configurable_error_handler` feature. [`default_error_handler`] returns the
+//! handler currently in use.
 //!
 //! Obel provides a number of pre-built error-handlers for you to use:
 //!
@@ -29,46 +24,29 @@
 //! signature:
 //!
 //! ```rust,ignore
-//! fn(ObelError, SystemErrorContext)
+//! fn(ObelError, ErrorContext)
 //! ```
 //!
-//! The [`SystemErrorContext`] allows you to access additional details relevant to providing
-//! context surrounding the system error – such as the system's [`name`] – in your error messages.
+//! This signature is available as the [`ErrorHandler`] type alias. The [`ErrorContext`] allows you
+//! to access additional details relevant to providing context surrounding the error – such as the
+//! [`name`](ErrorContext::name) of the failing system – in your error messages.
 //!
 //! For example:
 //!
-//! ```ignore
-//! # use obel_ecs::prelude::*;
-//! # use obel_ecs::schedule::ScheduleLabel;
-//! # use log::trace;
-//! # fn update() -> Result { Ok(()) }
-//! # #[derive(ScheduleLabel, Hash, Debug, PartialEq, Eq, Clone, Copy)]
-//! # struct MySchedule;
-//! # fn main() {
-//! let mut schedule = Schedule::new(MySchedule);
-//! schedule.add_systems(update);
-//! schedule.set_error_handler(|error, ctx| {
-//!     if ctx.name.ends_with("update") {
-//!         trace!("Nothing to see here, move along.");
+//! ```
+//! # use obel_ecs::error::{ErrorContext, ErrorHandler, ObelError};
+//! fn quiet_updates(error: ObelError, ctx: ErrorContext) {
+//!     if ctx.name().ends_with("update") {
 //!         return;
 //!     }
 //!
 //!     obel_ecs::error::error(error, ctx);
-//! });
-//! # }
-//! ```
+//! }
 //!
-//! If you need special handling of individual fallible systems, you can use Obel's [`system piping
-//! feature`] to capture the `Result` output of the system and handle it accordingly.
+//! let handler: ErrorHandler = quiet_updates;
+//! ```
 //!
-//! [`Schedule`]: crate::schedule::Schedule
 //! [`panic`]: panic()
-//! [`World`]: crate::world::World
-//! [`Schedule::set_error_handler`]: crate::schedule::Schedule::set_error_handler
-//! [`System`]: crate::system::System
-//! [`name`]: crate::system::System::name
-//! [`App::set_system_error_handler`]: ../../obel_app/struct.App.html#method.set_system_error_handler
-//! [`system piping feature`]: crate::system::In
 
 pub use err::*;
 pub use handler::*;
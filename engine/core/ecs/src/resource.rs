@@ -51,10 +51,10 @@ pub use obel_ecs_macros::Resource;
 /// ```
 ///
 /// This will compile since the `RefCell` is wrapped with `SyncCell`.
-/// ```ignore
+/// ```
 /// # use std::cell::RefCell;
 /// # use obel_ecs::resource::Resource;
-/// use obel_utils::synccell::SyncCell;
+/// use obel_platform::utils::SyncCell;
 ///
 /// #[derive(Resource)]
 /// struct ActuallySync {
@@ -66,7 +66,7 @@ pub use obel_ecs_macros::Resource;
 /// [`World`]: crate::world::World
 /// [`Res`]: crate::system::Res
 /// [`ResMut`]: crate::system::ResMut
-/// [`SyncCell`]: obel_utils::synccell::SyncCell
+/// [`SyncCell`]: obel_platform::utils::SyncCell
 #[diagnostic::on_unimplemented(
     message = "`{Self}` is not a `Resource`",
     label = "invalid `Resource`",
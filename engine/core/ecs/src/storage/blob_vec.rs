@@ -99,6 +99,27 @@ impl BlobVec {
         self.item_layout
     }
 
+    /// Returns the number of elements the vector can hold without reallocating.
+    ///
+    /// This is `usize::MAX` for zero-sized types.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of bytes currently allocated by the vector, i.e. the size of
+    /// [`layout`](Self::layout) repeated [`capacity`](Self::capacity) times.
+    ///
+    /// This is `0` for zero-sized types, which never allocate.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        if self.item_layout.size() == 0 {
+            return 0;
+        }
+        // SAFETY: the `data` allocation was made with this exact layout, so it can't overflow
+        unsafe { array_layout_unchecked(&self.item_layout, self.capacity) }.size()
+    }
+
     /// Reserves the minimum capacity for at least `additional` more elements to be inserted in the given `BlobVec`.
     /// After calling `reserve_exact`, capacity will be greater than or equal to `self.len() + additional`. Does nothing if
     /// the capacity is already sufficient.
@@ -642,6 +663,22 @@ mod tests {
         let _ = unsafe { BlobVec::new(item_layout, Some(drop), 0) };
     }
 
+    #[test]
+    fn blob_vec_allocated_bytes() {
+        // SAFETY: no drop is correct drop for `u64`.
+        let mut blob_vec = unsafe { BlobVec::new(Layout::new::<u64>(), None, 0) };
+        assert_eq!(blob_vec.allocated_bytes(), 0);
+
+        blob_vec.reserve_exact(10);
+        assert_eq!(blob_vec.capacity(), 10);
+        assert_eq!(blob_vec.allocated_bytes(), 80);
+
+        // SAFETY: no drop is correct drop for `()`.
+        let zst = unsafe { BlobVec::new(Layout::new::<()>(), None, 10) };
+        assert_eq!(zst.capacity(), usize::MAX);
+        assert_eq!(zst.allocated_bytes(), 0);
+    }
+
     #[test]
     #[should_panic(expected = "capacity overflow")]
     fn blob_vec_zst_size_overflow() {
@@ -0,0 +1,492 @@
+//! Cooperative cancellation of tasks.
+
+use alloc::vec::Vec;
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use futures_lite::FutureExt;
+use obel_platform::sync::{
+    Arc, Mutex, PoisonError, Weak,
+    atomic::{AtomicBool, Ordering},
+};
+
+use crate::Task;
+
+/// The wakers of the [`Cancelled`] futures of a token, each stored under a key kept by its
+/// future, so that it is replaced when polled again and removed when dropped.
+#[derive(Default)]
+struct WakerSlab {
+    entries: Vec<Option<Waker>>,
+    free: Vec<usize>,
+}
+
+impl WakerSlab {
+    fn insert(&mut self, waker: Waker) -> usize {
+        match self.free.pop() {
+            Some(key) => {
+                self.entries[key] = Some(waker);
+                key
+            }
+            None => {
+                self.entries.push(Some(waker));
+                self.entries.len() - 1
+            }
+        }
+    }
+
+    /// Replaces the waker stored under `key`, returning `false` if it has been removed.
+    fn update(&mut self, key: usize, waker: &Waker) -> bool {
+        match self.entries.get_mut(key) {
+            Some(Some(stored)) => {
+                if !stored.will_wake(waker) {
+                    stored.clone_from(waker);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn remove(&mut self, key: usize) -> Option<Waker> {
+        let waker = self.entries.get_mut(key)?.take()?;
+        self.free.push(key);
+        Some(waker)
+    }
+
+    fn drain(&mut self) -> Vec<Waker> {
+        (0..self.entries.len()).filter_map(|key| self.remove(key)).collect()
+    }
+}
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    wakers: Mutex<WakerSlab>,
+    children: Mutex<Vec<Weak<TokenState>>>,
+}
+
+impl TokenState {
+    fn cancel(&self) {
+        if self.cancelled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner).drain();
+        for waker in wakers {
+            waker.wake();
+        }
+        let children =
+            core::mem::take(&mut *self.children.lock().unwrap_or_else(PoisonError::into_inner));
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// A token signalling that some work should stop.
+///
+/// Cancellation is cooperative: a long-running task holds a clone of the token and checks
+/// [`is_cancelled`](Self::is_cancelled) between steps, or awaits
+/// [`cancelled`](Self::cancelled) alongside its work. All clones share the same state, so
+/// cancelling any of them cancels all of them.
+///
+/// Tokens form a tree: cancelling a token also cancels every token created from it with
+/// [`child_token`](Self::child_token), but cancelling a child leaves its parent untouched.
+///
+/// ```
+/// # use obel_tasks::{CancellationToken, block_on};
+/// let token = CancellationToken::new();
+/// let planner = token.child_token();
+///
+/// // e.g. when the entity running the planner despawns:
+/// token.cancel();
+///
+/// assert!(planner.is_cancelled());
+/// assert_eq!(block_on(planner.run_until_cancelled(async { "plan" })), None);
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    /// Creates a new token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that is cancelled when `self` is, or when it is cancelled itself.
+    pub fn child_token(&self) -> Self {
+        let child = Self::new();
+        if self.is_cancelled() {
+            child.cancel();
+            return child;
+        }
+        let mut children = self.state.children.lock().unwrap_or_else(PoisonError::into_inner);
+        // keep the list from growing with children that have been dropped
+        children.retain(|child| child.strong_count() > 0);
+        children.push(Arc::downgrade(&child.state));
+        drop(children);
+        // `self` may have been cancelled while the child was registered
+        if self.is_cancelled() {
+            child.cancel();
+        }
+        child
+    }
+
+    /// Cancels this token and its children, and wakes every task awaiting
+    /// [`cancelled`](Self::cancelled). Cancelling a cancelled token does nothing.
+    pub fn cancel(&self) {
+        self.state.cancel();
+    }
+
+    /// Returns `true` if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled()
+    }
+
+    /// Returns a future completing once this token is cancelled.
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled {
+            token: self,
+            key: None,
+        }
+    }
+
+    /// Runs `future` until it completes, returning its output, or until this token is
+    /// cancelled, returning `None`. The future is dropped on cancellation, and is not polled
+    /// at all if the token is already cancelled.
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        async {
+            self.cancelled().await;
+            None
+        }
+        .or(async { Some(future.await) })
+        .await
+    }
+
+    /// Returns a guard cancelling this token when dropped.
+    pub fn drop_guard(self) -> DropGuard {
+        DropGuard {
+            token: Some(self),
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken").field("is_cancelled", &self.is_cancelled()).finish()
+    }
+}
+
+/// The future returned by [`CancellationToken::cancelled`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Cancelled<'a> {
+    token: &'a CancellationToken,
+    /// The key of the waker registered by the last poll.
+    key: Option<usize>,
+}
+
+impl Cancelled<'_> {
+    fn unregister(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.token.state.wakers.lock().unwrap_or_else(PoisonError::into_inner).remove(key);
+        }
+    }
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let state = &self.token.state;
+        if state.is_cancelled() {
+            self.unregister();
+            return Poll::Ready(());
+        }
+        let mut wakers = state.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        let registered = self.key.is_some_and(|key| wakers.update(key, cx.waker()));
+        if !registered {
+            let key = wakers.insert(cx.waker().clone());
+            drop(wakers);
+            self.key = Some(key);
+        } else {
+            drop(wakers);
+        }
+        // the token may have been cancelled before the waker was registered
+        if self.token.state.is_cancelled() {
+            self.unregister();
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Cancelled<'_> {
+    fn drop(&mut self) {
+        self.unregister();
+    }
+}
+
+/// Cancels a [`CancellationToken`] when dropped, see [`CancellationToken::drop_guard`].
+#[derive(Debug)]
+pub struct DropGuard {
+    token: Option<CancellationToken>,
+}
+
+impl DropGuard {
+    /// Returns the token without cancelling it.
+    pub fn disarm(mut self) -> CancellationToken {
+        self.token.take().unwrap()
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(token) = &self.token {
+            token.cancel();
+        }
+    }
+}
+
+/// A set of fallible [`Task`]s that are cancelled together.
+///
+/// The group owns a [`CancellationToken`], which its tasks should observe. All tasks are
+/// cancelled, both through the token and by dropping them, when:
+///
+/// - the group is dropped, e.g. because the task owning it was cancelled,
+/// - one of the tasks fails while the group is [joined](Self::join),
+/// - [`cancel`](Self::cancel) is called.
+///
+/// A group created with [`with_parent`](Self::with_parent) is also cancelled with its parent
+/// token, which nests groups.
+///
+/// ```
+/// # use obel_tasks::{TaskGroup, TaskPool, block_on};
+/// let pool = TaskPool::new();
+/// let mut group = TaskGroup::new();
+/// for i in 0..3 {
+///     let token = group.token();
+///     group.push(pool.spawn(async move {
+///         if token.is_cancelled() {
+///             return Err("cancelled");
+///         }
+///         Ok(i * 2)
+///     }));
+/// }
+/// assert_eq!(block_on(group.join()), Ok(vec![0, 2, 4]));
+/// ```
+#[derive(Debug)]
+pub struct TaskGroup<T, E> {
+    token: CancellationToken,
+    tasks: Vec<Task<Result<T, E>>>,
+}
+
+impl<T, E> Default for TaskGroup<T, E> {
+    fn default() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            tasks: Vec::new(),
+        }
+    }
+}
+
+impl<T, E> TaskGroup<T, E> {
+    /// Creates an empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty group cancelled together with `parent`.
+    pub fn with_parent(parent: &CancellationToken) -> Self {
+        Self {
+            token: parent.child_token(),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Returns the token of this group, to be passed to its tasks.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Adds a task to this group.
+    pub fn push(&mut self, task: Task<Result<T, E>>) {
+        self.tasks.push(task);
+    }
+
+    /// Returns the number of tasks in this group.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if this group has no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Cancels the token of this group and drops its tasks.
+    pub fn cancel(&mut self) {
+        self.token.cancel();
+        self.tasks.clear();
+    }
+
+    /// Waits for all tasks, returning their outputs in the order they were pushed.
+    ///
+    /// If a task fails, the others are cancelled and its error is returned.
+    pub async fn join(mut self) -> Result<Vec<T>, E> {
+        let mut tasks: Vec<Option<Task<Result<T, E>>>> =
+            core::mem::take(&mut self.tasks).into_iter().map(Some).collect();
+        let mut outputs: Vec<Option<T>> = tasks.iter().map(|_| None).collect();
+        let mut remaining = tasks.len();
+
+        let result = futures_lite::future::poll_fn(|cx| {
+            for (slot, output) in tasks.iter_mut().zip(&mut outputs) {
+                let Some(task) = slot else {
+                    continue;
+                };
+                if let Poll::Ready(result) = Pin::new(task).poll(cx) {
+                    *slot = None;
+                    remaining -= 1;
+                    match result {
+                        Ok(value) => *output = Some(value),
+                        Err(error) => return Poll::Ready(Err(error)),
+                    }
+                }
+            }
+            if remaining == 0 {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        match result {
+            Ok(()) => Ok(outputs.into_iter().map(Option::unwrap).collect()),
+            Err(error) => {
+                // dropping `self` and the remaining tasks cancels them
+                self.token.cancel();
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<T, E> Drop for TaskGroup<T, E> {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskPool, block_on};
+    use alloc::boxed::Box;
+    use futures_lite::future;
+
+    #[test]
+    fn cancel_wakes_waiters() {
+        let token = CancellationToken::new();
+        let mut waiter = Box::pin(token.cancelled());
+        assert!(block_on(future::poll_once(&mut waiter)).is_none());
+        token.clone().cancel();
+        assert_eq!(block_on(waiter), ());
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn dropped_waiters_do_not_leak_wakers() {
+        let token = CancellationToken::new();
+        for _ in 0..1000 {
+            let mut waiter = Box::pin(token.cancelled());
+            assert!(block_on(future::poll_once(&mut waiter)).is_none());
+            // polling again replaces the waker instead of adding one
+            assert!(block_on(future::poll_once(&mut waiter)).is_none());
+            drop(waiter);
+            assert!(
+                block_on(future::poll_once(token.run_until_cancelled(future::pending::<()>())))
+                    .is_none()
+            );
+        }
+        let wakers = token.state.wakers.lock().unwrap();
+        assert!(wakers.entries.len() <= 1);
+        assert!(wakers.entries.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn children_follow_parent_but_not_the_other_way() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let grandchild = child.child_token();
+
+        child.cancel();
+        assert!(!parent.is_cancelled());
+        assert!(grandchild.is_cancelled());
+
+        let sibling = parent.child_token();
+        parent.cancel();
+        assert!(sibling.is_cancelled());
+        assert!(parent.child_token().is_cancelled());
+    }
+
+    #[test]
+    fn drop_guard_cancels_unless_disarmed() {
+        let token = CancellationToken::new();
+        drop(token.clone().drop_guard());
+        assert!(token.is_cancelled());
+
+        let token = CancellationToken::new();
+        let _ = token.clone().drop_guard().disarm();
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn run_until_cancelled() {
+        let token = CancellationToken::new();
+        assert_eq!(block_on(token.run_until_cancelled(async { 1 })), Some(1));
+        token.cancel();
+        assert_eq!(block_on(token.run_until_cancelled(future::pending::<()>())), None);
+    }
+
+    #[test]
+    fn group_failure_cancels_siblings() {
+        let pool = TaskPool::new();
+        let mut group = TaskGroup::<(), &str>::new();
+
+        let token = group.token();
+        group.push(pool.spawn(async move {
+            token.cancelled().await;
+            Ok(())
+        }));
+        group.push(pool.spawn(async { Err("failed") }));
+        let token = group.token();
+
+        assert_eq!(block_on(group.join()), Err("failed"));
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn dropping_group_cancels_token() {
+        let parent = CancellationToken::new();
+        let group = TaskGroup::<(), ()>::with_parent(&parent);
+        let token = group.token();
+        drop(group);
+        assert!(token.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        let group = TaskGroup::<(), ()>::with_parent(&parent);
+        let token = group.token();
+        parent.cancel();
+        assert!(token.is_cancelled());
+    }
+}
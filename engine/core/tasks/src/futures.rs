@@ -1,11 +1,13 @@
 #![expect(unsafe_code, reason = "Futures require unsafe code.")]
 
 //! Utilities for working with [`Future`]s.
+use alloc::{boxed::Box, vec::Vec};
 use core::{
     future::Future,
     pin::Pin,
     task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
 };
+use futures_lite::{FutureExt, future::poll_fn};
 
 use conditional_send::*;
 
@@ -32,7 +34,7 @@ impl<T: Future + ConditionalSend> ConditionalSendFuture for T {}
 
 /// An owned and dynamically typed Future used when you can't
 /// statically type your result or need to add some indirection.
-pub type BoxedFuture<'a, T> = Pin<alloc::boxed::Box<dyn ConditionalSendFuture<Output = T> + 'a>>;
+pub type BoxedFuture<'a, T> = Pin<Box<dyn ConditionalSendFuture<Output = T> + 'a>>;
 
 /// Consumes a future, polls it once, and immediately returns the output
 /// or returns `None` if it wasn't ready yet.
@@ -65,6 +67,97 @@ pub fn check_ready<F: Future + Unpin>(future: &mut F) -> Option<F::Output> {
     }
 }
 
+/// The output of [`select`]: which of two futures completed first, with its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<A, B> {
+    /// The first future completed first.
+    Left(A),
+    /// The second future completed first.
+    Right(B),
+}
+
+/// Waits for the first of two futures to complete, and drops the other one.
+///
+/// The futures are polled in order, so `a` wins if both are ready.
+///
+/// ```
+/// # use obel_tasks::{block_on, futures::{select, Either}};
+/// # use futures_lite::future::pending;
+/// let output = block_on(select(pending::<()>(), async { "done" }));
+/// assert_eq!(output, Either::Right("done"));
+/// ```
+pub async fn select<A: Future, B: Future>(a: A, b: B) -> Either<A::Output, B::Output> {
+    async { Either::Left(a.await) }.or(async { Either::Right(b.await) }).await
+}
+
+/// Waits for the first of `futures` to complete, returning its index and output. The other
+/// futures are dropped.
+///
+/// The futures are polled in order, so the first ready one wins.
+///
+/// ```
+/// # use obel_tasks::{block_on, futures::race};
+/// # use futures_lite::future::{pending, ready, Boxed, FutureExt};
+/// let futures: [Boxed<u32>; 3] = [pending().boxed(), ready(1).boxed(), ready(2).boxed()];
+/// assert_eq!(block_on(race(futures)), (1, 1));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `futures` is empty.
+pub async fn race<I>(futures: I) -> (usize, <I::Item as Future>::Output)
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    assert!(!futures.is_empty(), "cannot race an empty set of futures");
+    poll_fn(|cx| {
+        futures
+            .iter_mut()
+            .enumerate()
+            .find_map(|(index, future)| match future.as_mut().poll(cx) {
+                Poll::Ready(output) => Some(Poll::Ready((index, output))),
+                Poll::Pending => None,
+            })
+            .unwrap_or(Poll::Pending)
+    })
+    .await
+}
+
+/// Waits for all `futures` to complete, returning their outputs in order.
+///
+/// ```
+/// # use obel_tasks::{block_on, futures::join_all};
+/// let outputs = block_on(join_all((0..3).map(|i| async move { i * 2 })));
+/// assert_eq!(outputs, [0, 2, 4]);
+/// ```
+pub async fn join_all<I>(futures: I) -> Vec<<I::Item as Future>::Output>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    let mut futures: Vec<_> = futures.into_iter().map(|future| (Box::pin(future), None)).collect();
+    poll_fn(|cx| {
+        let mut pending = false;
+        for (future, output) in &mut futures {
+            if output.is_none() {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(value) => *output = Some(value),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+    futures.into_iter().map(|(_, output)| output.unwrap()).collect()
+}
+
 fn noop_clone(_data: *const ()) -> RawWaker {
     noop_raw_waker()
 }
@@ -10,6 +10,9 @@ extern crate std;
 pub use task::Task;
 pub mod futures;
 
+mod cancellation;
+pub use cancellation::{CancellationToken, Cancelled, DropGuard, TaskGroup};
+
 #[cfg_attr(all(target_arch = "wasm32", feature = "web"), path = "wasm_task.rs")]
 mod task;
 
@@ -29,6 +32,12 @@ cfg_if::cfg_if! {
   }
 }
 
+mod priority;
+pub use priority::TaskPriority;
+
+mod timer;
+pub use timer::{Interval, MockClock, Sleep, TimedOut, Timers, interval, sleep, timeout};
+
 mod task_pool_macro;
 pub use futures_lite::future::poll_once;
 #[cfg(not(all(target_arch = "wasm32", feature = "web")))]
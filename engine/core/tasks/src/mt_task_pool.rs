@@ -1,17 +1,29 @@
-use alloc::{boxed::Box, format, string::String, vec::Vec};
-use core::{future::Future, marker::PhantomData, mem, panic::AssertUnwindSafe};
+use alloc::{boxed::Box, collections::BinaryHeap, format, string::String, vec::Vec};
+use core::{
+    cmp::Ordering,
+    future::Future,
+    marker::PhantomData,
+    mem,
+    panic::AssertUnwindSafe,
+    sync::atomic::{self, AtomicU64},
+};
 use std::{
     thread::{self, JoinHandle},
     thread_local,
 };
 
 use crate::executor::FallibleTask;
+use async_task::Runnable;
 use concurrent_queue::ConcurrentQueue;
-use futures_lite::FutureExt;
-use obel_platform::sync::Arc;
+use futures_lite::{FutureExt, future};
+use obel_platform::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Instant,
+};
 
 use crate::{
-    Task, block_on,
+    Task, TaskPriority, block_on,
+    executor::{Executor, LocalExecutor},
     thread_executor::{ThreadExecutor, ThreadExecutorTicker},
 };
 
@@ -25,6 +37,203 @@ impl Drop for CallOnDrop {
     }
 }
 
+/// A runnable task waiting in a [`DeadlineQueue`].
+#[derive(Debug)]
+struct DeadlineEntry {
+    deadline: Instant,
+    // Breaks ties between equal deadlines in spawn order.
+    sequence: u64,
+    runnable: Runnable,
+}
+
+impl PartialEq for DeadlineEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DeadlineEntry {}
+
+impl PartialOrd for DeadlineEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DeadlineEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` is a max-heap, so the earliest deadline must compare as the greatest.
+        (other.deadline, other.sequence).cmp(&(self.deadline, self.sequence))
+    }
+}
+
+/// Runnable tasks with a deadline, run earliest deadline first.
+///
+/// Each scheduled task rings the doorbell once, so every ring is answered by exactly one
+/// worker popping one task.
+#[derive(Debug)]
+struct DeadlineQueue {
+    heap: Mutex<BinaryHeap<DeadlineEntry>>,
+    sequence: AtomicU64,
+    doorbell_tx: async_channel::Sender<()>,
+    doorbell_rx: async_channel::Receiver<()>,
+}
+
+impl Default for DeadlineQueue {
+    fn default() -> Self {
+        let (doorbell_tx, doorbell_rx) = async_channel::unbounded();
+        Self {
+            heap: Mutex::new(BinaryHeap::new()),
+            sequence: AtomicU64::new(0),
+            doorbell_tx,
+            doorbell_rx,
+        }
+    }
+}
+
+impl DeadlineQueue {
+    /// Returns the schedule function of a task with the given `deadline`.
+    fn schedule(self: &Arc<Self>, deadline: Instant) -> impl Fn(Runnable) + Send + Sync + 'static {
+        let queue = Arc::clone(self);
+        move |runnable| queue.push(deadline, runnable)
+    }
+
+    fn push(&self, deadline: Instant, runnable: Runnable) {
+        let sequence = self.sequence.fetch_add(1, atomic::Ordering::Relaxed);
+        self.heap.lock().unwrap_or_else(PoisonError::into_inner).push(DeadlineEntry {
+            deadline,
+            sequence,
+            runnable,
+        });
+        // the queue owns both ends of the channel, so it can never be closed
+        self.doorbell_tx.try_send(()).unwrap();
+    }
+
+    /// The number of tasks run in a row by [`run_forever`](Self::run_forever).
+    const BATCH: usize = 200;
+
+    /// Runs scheduled tasks forever, earliest deadline first, in batches of up to
+    /// [`BATCH`](Self::BATCH) tasks. It yields after each batch, or once no task is left, so
+    /// that more urgent work is polled in between.
+    async fn run_forever<T>(&self) -> T {
+        loop {
+            if self.doorbell_rx.recv().await.is_ok() {
+                self.run_earliest();
+                for _ in 1..Self::BATCH {
+                    if self.doorbell_rx.try_recv().is_err() {
+                        break;
+                    }
+                    self.run_earliest();
+                }
+            }
+            future::yield_now().await;
+        }
+    }
+
+    /// Runs the task with the earliest deadline, answering one ring of the doorbell.
+    fn run_earliest(&self) {
+        let entry = self.heap.lock().unwrap_or_else(PoisonError::into_inner).pop();
+        if let Some(entry) = entry {
+            entry.runnable.run();
+        }
+    }
+
+    /// Drops all waiting tasks, which cancels them.
+    fn clear(&self) {
+        let heap = mem::take(&mut *self.heap.lock().unwrap_or_else(PoisonError::into_inner));
+        drop(heap);
+    }
+}
+
+/// The tasks of one [`TaskPriority`].
+#[derive(Debug, Default)]
+struct PriorityLevel<'a> {
+    executor: Executor<'a>,
+    deadlines: Arc<DeadlineQueue>,
+}
+
+impl PriorityLevel<'_> {
+    /// Runs `future` to completion, running tasks of this priority whenever it is pending.
+    /// Tasks with a deadline are preferred.
+    async fn run<T>(&self, future: impl Future<Output = T>) -> T {
+        self.executor.run(future.or(self.deadlines.run_forever())).await
+    }
+}
+
+/// The executors of a [`TaskPool`], one per [`TaskPriority`].
+#[derive(Debug, Default)]
+struct PriorityExecutors<'a> {
+    levels: [PriorityLevel<'a>; 3],
+}
+
+impl<'a> PriorityExecutors<'a> {
+    fn level(&self, priority: TaskPriority) -> &PriorityLevel<'a> {
+        &self.levels[priority as usize]
+    }
+
+    /// Runs `future` to completion, running tasks whenever it is pending.
+    ///
+    /// Each priority is driven by the runner of its executor, nested so that `future` and the
+    /// more urgent priorities are polled first whenever a less urgent runner yields, i.e.
+    /// between two of its batches of tasks. The thread-local executor, if any, runs after high
+    /// priority tasks. Background tasks only run if `background` is `true`.
+    async fn run<T>(
+        &self,
+        local_executor: Option<&LocalExecutor<'_>>,
+        background: bool,
+        future: impl Future<Output = T>,
+    ) -> T {
+        let [high, normal, low] = &self.levels;
+        let high = high.run(future);
+        let local = async {
+            match local_executor {
+                Some(local_executor) => local_executor.run(high).await,
+                None => high.await,
+            }
+        };
+        let normal = normal.run(local);
+        if background {
+            low.run(normal).await
+        } else {
+            normal.await
+        }
+    }
+
+    /// Runs tasks of every priority forever, so that the caller can poll other futures.
+    async fn run_forever(&self) {
+        self.run(None, true, future::pending::<()>()).await;
+    }
+
+    /// Spawns a task at `priority` with an optional `deadline`.
+    fn spawn<T: Send + 'a>(
+        &self,
+        priority: TaskPriority,
+        deadline: Option<Instant>,
+        future: impl Future<Output = T> + Send + 'a,
+    ) -> async_task::Task<T> {
+        let level = self.level(priority);
+        let Some(deadline) = deadline else {
+            return level.executor.spawn(future);
+        };
+        #[expect(unsafe_code, reason = "Scoped tasks borrow from their scope.")]
+        // SAFETY:
+        // - `future` and `schedule` are `Send`, so the runnable may run on any thread.
+        // - `schedule` is `'static`. `future` outlives the task, since `'a` is either `'static`
+        //   or the lifetime of a scope, which drives or cancels its tasks before returning.
+        let (runnable, task) =
+            unsafe { async_task::spawn_unchecked(future, level.deadlines.schedule(deadline)) };
+        runnable.schedule();
+        task
+    }
+
+    /// Drops all tasks waiting in the deadline queues.
+    fn clear_deadlines(&self) {
+        for level in &self.levels {
+            level.deadlines.clear();
+        }
+    }
+}
+
 /// Used to create a [`TaskPool`]
 #[derive(Default)]
 #[must_use]
@@ -37,6 +246,8 @@ pub struct TaskPoolBuilder {
     /// Allows customizing the name of the threads - helpful for debugging. If set, threads will
     /// be named `<thread_name> (<thread_index>)`, i.e. `"MyThreadPool (2)"`.
     thread_name: Option<String>,
+    /// If set, the number of threads that never run [`TaskPriority::Background`] tasks.
+    reserved_threads: Option<usize>,
 
     on_thread_spawn: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
     on_thread_destroy: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
@@ -55,6 +266,16 @@ impl TaskPoolBuilder {
         self
     }
 
+    /// Override the number of threads that never run [`TaskPriority::Background`] tasks, so
+    /// that they stay available for more urgent ones however much background work is queued.
+    ///
+    /// If unset, a quarter of the threads are reserved, and at least one when the pool has
+    /// more than one thread. It is clamped so that at least one thread runs background tasks.
+    pub fn reserved_threads(mut self, reserved_threads: usize) -> Self {
+        self.reserved_threads = Some(reserved_threads);
+        self
+    }
+
     /// Override the stack size of the threads created for the pool
     pub fn stack_size(mut self, stack_size: usize) -> Self {
         self.stack_size = Some(stack_size);
@@ -126,8 +347,8 @@ impl TaskPoolBuilder {
 /// will still execute a task, even if it is dropped.
 #[derive(Debug)]
 pub struct TaskPool {
-    /// The executor for the pool.
-    executor: Arc<crate::executor::Executor<'static>>,
+    /// The executors for the pool, one per [`TaskPriority`].
+    executors: Arc<PriorityExecutors<'static>>,
 
     // The inner state of the pool.
     threads: Vec<JoinHandle<()>>,
@@ -136,7 +357,7 @@ pub struct TaskPool {
 
 impl TaskPool {
     thread_local! {
-        static LOCAL_EXECUTOR: crate::executor::LocalExecutor<'static> = const { crate::executor::LocalExecutor::new() };
+        static LOCAL_EXECUTOR: LocalExecutor<'static> = const { LocalExecutor::new() };
         static THREAD_EXECUTOR: Arc<ThreadExecutor<'static>> = Arc::new(ThreadExecutor::new());
     }
 
@@ -153,13 +374,22 @@ impl TaskPool {
     fn new_internal(builder: TaskPoolBuilder) -> Self {
         let (shutdown_tx, shutdown_rx) = async_channel::unbounded::<()>();
 
-        let executor = Arc::new(crate::executor::Executor::new());
+        let executors = Arc::new(PriorityExecutors::default());
 
         let num_threads = builder.num_threads.unwrap_or_else(crate::available_parallelism);
 
+        let reserved_threads = builder
+            .reserved_threads
+            .unwrap_or(if num_threads > 1 {
+                (num_threads / 4).max(1)
+            } else {
+                0
+            })
+            .min(num_threads.saturating_sub(1));
         let threads = (0..num_threads)
             .map(|i| {
-                let ex = Arc::clone(&executor);
+                let runs_background = i < num_threads - reserved_threads;
+                let ex = Arc::clone(&executors);
                 let shutdown_rx = shutdown_rx.clone();
 
                 let thread_name = if let Some(thread_name) = builder.thread_name.as_deref() {
@@ -186,12 +416,11 @@ impl TaskPool {
                             let _destructor = CallOnDrop(on_thread_destroy);
                             loop {
                                 let res = std::panic::catch_unwind(|| {
-                                    let tick_forever = async move {
-                                        loop {
-                                            local_executor.tick().await;
-                                        }
-                                    };
-                                    block_on(ex.run(tick_forever.or(shutdown_rx.recv())))
+                                    block_on(ex.run(
+                                        Some(local_executor),
+                                        runs_background,
+                                        shutdown_rx.recv(),
+                                    ))
                                 });
                                 if let Ok(value) = res {
                                     // Use unwrap_err because we expect a Closed error
@@ -206,7 +435,7 @@ impl TaskPool {
             .collect();
 
         Self {
-            executor,
+            executors,
             // The caller can use the returned thread handle to call other functions,
             // which will then spawn tasks to the local executor.
             // This allows for efficient task management and execution
@@ -371,9 +600,9 @@ impl TaskPool {
         // transmute the lifetimes to 'env here to appease the compiler as it is unable to validate safety.
         // Any usages of the references passed into `Scope` must be accessed through
         // the transmuted reference for the rest of this function.
-        let executor: &crate::executor::Executor = &self.executor;
+        let executors: &PriorityExecutors = &self.executors;
         // SAFETY: As above, all futures must complete in this function so we can change the lifetime
-        let executor: &'env crate::executor::Executor = unsafe { mem::transmute(executor) };
+        let executors: &'env PriorityExecutors = unsafe { mem::transmute(executors) };
         // SAFETY: As above, all futures must complete in this function so we can change the lifetime
         let external_executor: &'env ThreadExecutor<'env> =
             unsafe { mem::transmute(external_executor) };
@@ -388,7 +617,7 @@ impl TaskPool {
         > = unsafe { mem::transmute(&spawned) };
 
         let scope = Scope {
-            executor,
+            executors,
             external_executor,
             scope_executor,
             spawned,
@@ -436,7 +665,7 @@ impl TaskPool {
                 match (external_ticker, tick_task_pool_executor) {
                     (Some(external_ticker), true) => {
                         Self::execute_global_external_scope(
-                            executor,
+                            executors,
                             external_ticker,
                             scope_ticker,
                             get_results,
@@ -449,7 +678,7 @@ impl TaskPool {
                     }
                     // either external_executor is none or it is same as scope_executor
                     (None, true) => {
-                        Self::execute_global_scope(executor, scope_ticker, get_results).await
+                        Self::execute_global_scope(executors, scope_ticker, get_results).await
                     }
                     (None, false) => Self::execute_scope(scope_ticker, get_results).await,
                 }
@@ -459,7 +688,7 @@ impl TaskPool {
 
     #[inline]
     async fn execute_global_external_scope<'scope, 'ticker, T>(
-        executor: &'scope crate::executor::Executor<'scope>,
+        executors: &'scope PriorityExecutors<'scope>,
         external_ticker: ThreadExecutorTicker<'scope, 'ticker>,
         scope_ticker: ThreadExecutorTicker<'scope, 'ticker>,
         get_results: impl Future<Output = Vec<T>>,
@@ -475,8 +704,10 @@ impl TaskPool {
                 };
                 // we don't care if it errors. If a scoped task errors it will propagate
                 // to get_results
-                let _result =
-                    AssertUnwindSafe(executor.run(tick_forever)).catch_unwind().await.is_ok();
+                let _result = AssertUnwindSafe(tick_forever.or(executors.run_forever()))
+                    .catch_unwind()
+                    .await
+                    .is_ok();
             }
         };
         get_results.or(execute_forever).await
@@ -503,7 +734,7 @@ impl TaskPool {
 
     #[inline]
     async fn execute_global_scope<'scope, 'ticker, T>(
-        executor: &'scope crate::executor::Executor<'scope>,
+        executors: &'scope PriorityExecutors<'scope>,
         scope_ticker: ThreadExecutorTicker<'scope, 'ticker>,
         get_results: impl Future<Output = Vec<T>>,
     ) -> Vec<T> {
@@ -514,8 +745,10 @@ impl TaskPool {
                         scope_ticker.tick().await;
                     }
                 };
-                let _result =
-                    AssertUnwindSafe(executor.run(tick_forever)).catch_unwind().await.is_ok();
+                let _result = AssertUnwindSafe(tick_forever.or(executors.run_forever()))
+                    .catch_unwind()
+                    .await
+                    .is_ok();
             }
         };
         get_results.or(execute_forever).await
@@ -551,7 +784,48 @@ impl TaskPool {
     where
         T: Send + 'static,
     {
-        Task::new(self.executor.spawn(future))
+        self.spawn_with_priority(TaskPriority::Normal, future)
+    }
+
+    /// Spawns a static future onto the thread pool at the given [`TaskPriority`].
+    ///
+    /// See [`TaskPool::spawn`] for more details.
+    ///
+    /// ```
+    /// use obel_tasks::{TaskPool, TaskPriority, block_on};
+    ///
+    /// let pool = TaskPool::new();
+    /// let task = pool.spawn_with_priority(TaskPriority::Background, async { 1 + 1 });
+    /// assert_eq!(block_on(task), 2);
+    /// ```
+    pub fn spawn_with_priority<T>(
+        &self,
+        priority: TaskPriority,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> Task<T>
+    where
+        T: Send + 'static,
+    {
+        Task::new(self.executors.spawn(priority, None, future))
+    }
+
+    /// Spawns a static future onto the thread pool at the given [`TaskPriority`], ahead of the
+    /// tasks of that priority without a deadline. Among tasks with a deadline, the earliest
+    /// deadline runs first.
+    ///
+    /// The deadline only orders tasks: a task is not canceled when its deadline passes.
+    ///
+    /// See [`TaskPool::spawn`] for more details.
+    pub fn spawn_with_deadline<T>(
+        &self,
+        priority: TaskPriority,
+        deadline: Instant,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> Task<T>
+    where
+        T: Send + 'static,
+    {
+        Task::new(self.executors.spawn(priority, Some(deadline), future))
     }
 
     /// Spawns a static future on the thread-local async executor for the
@@ -585,7 +859,7 @@ impl TaskPool {
     /// ```
     pub fn with_local_executor<F, R>(&self, f: F) -> R
     where
-        F: FnOnce(&crate::executor::LocalExecutor) -> R,
+        F: FnOnce(&LocalExecutor) -> R,
     {
         Self::LOCAL_EXECUTOR.with(f)
     }
@@ -608,6 +882,9 @@ impl Drop for TaskPool {
                 res.expect("Task thread panicked while executing.");
             }
         }
+
+        // Waiting tasks keep their queue alive through their schedule function.
+        self.executors.clear_deadlines();
     }
 }
 
@@ -616,7 +893,7 @@ impl Drop for TaskPool {
 /// For more information, see [`TaskPool::scope`].
 #[derive(Debug)]
 pub struct Scope<'scope, 'env: 'scope, T> {
-    executor: &'scope crate::executor::Executor<'scope>,
+    executors: &'scope PriorityExecutors<'scope>,
     external_executor: &'scope ThreadExecutor<'scope>,
     scope_executor: &'scope ThreadExecutor<'scope>,
     spawned: &'scope ConcurrentQueue<FallibleTask<Result<T, Box<(dyn core::any::Any + Send)>>>>,
@@ -635,7 +912,42 @@ impl<'scope, 'env, T: Send + 'scope> Scope<'scope, 'env, T> {
     ///
     /// For more information, see [`TaskPool::scope`].
     pub fn spawn<Fut: Future<Output = T> + 'scope + Send>(&self, f: Fut) {
-        let task = self.executor.spawn(AssertUnwindSafe(f).catch_unwind()).fallible();
+        self.spawn_with_priority(TaskPriority::Normal, f);
+    }
+
+    /// Spawns a scoped future onto the thread pool at the given [`TaskPriority`].
+    ///
+    /// See [`Scope::spawn`] for more details.
+    pub fn spawn_with_priority<Fut: Future<Output = T> + 'scope + Send>(
+        &self,
+        priority: TaskPriority,
+        f: Fut,
+    ) {
+        self.spawn_inner(priority, None, f);
+    }
+
+    /// Spawns a scoped future onto the thread pool at the given [`TaskPriority`], ahead of the
+    /// tasks of that priority without a deadline. Among tasks with a deadline, the earliest
+    /// deadline runs first.
+    ///
+    /// See [`Scope::spawn`] for more details.
+    pub fn spawn_with_deadline<Fut: Future<Output = T> + 'scope + Send>(
+        &self,
+        priority: TaskPriority,
+        deadline: Instant,
+        f: Fut,
+    ) {
+        self.spawn_inner(priority, Some(deadline), f);
+    }
+
+    fn spawn_inner<Fut: Future<Output = T> + 'scope + Send>(
+        &self,
+        priority: TaskPriority,
+        deadline: Option<Instant>,
+        f: Fut,
+    ) {
+        let task =
+            self.executors.spawn(priority, deadline, AssertUnwindSafe(f).catch_unwind()).fallible();
         // ConcurrentQueue only errors when closed or full, but we never
         // close and use an unbounded queue, so it is safe to unwrap
         self.spawned.push(task).unwrap();
@@ -946,4 +1258,125 @@ mod tests {
 
         assert_eq!(count.load(Ordering::Acquire), 1);
     }
+
+    /// Occupies the only thread of `pool` until the returned sender is dropped.
+    fn block_single_thread(pool: &TaskPool) -> std::sync::mpsc::Sender<()> {
+        let started = Arc::new(Barrier::new(2));
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let started_clone = started.clone();
+        pool.spawn_with_priority(TaskPriority::High, async move {
+            started_clone.wait();
+            let _ = release_rx.recv();
+        })
+        .detach();
+        started.wait();
+        release_tx
+    }
+
+    #[test]
+    fn test_priorities() {
+        let pool = TaskPoolBuilder::new().num_threads(1).build();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let release = block_single_thread(&pool);
+        let tasks: Vec<_> = [TaskPriority::Background, TaskPriority::Normal, TaskPriority::High]
+            .into_iter()
+            .map(|priority| {
+                let order = order.clone();
+                pool.spawn_with_priority(priority, async move {
+                    order.lock().unwrap().push(priority);
+                })
+            })
+            .collect();
+        drop(release);
+
+        for task in tasks {
+            block_on(task);
+        }
+        assert_eq!(*order.lock().unwrap(), TaskPriority::ALL);
+    }
+
+    #[test]
+    fn test_deadlines() {
+        let pool = TaskPoolBuilder::new().num_threads(1).build();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let now = Instant::now();
+
+        let release = block_single_thread(&pool);
+        let spawn = |name: &'static str, deadline: Option<Instant>| {
+            let order = order.clone();
+            let future = async move { order.lock().unwrap().push(name) };
+            match deadline {
+                Some(deadline) => pool.spawn_with_deadline(TaskPriority::Normal, deadline, future),
+                None => pool.spawn(future),
+            }
+        };
+        let tasks = [
+            spawn("none", None),
+            spawn("late", Some(now + core::time::Duration::from_millis(20))),
+            spawn("early", Some(now + core::time::Duration::from_millis(10))),
+        ];
+        drop(release);
+
+        for task in tasks {
+            block_on(task);
+        }
+        assert_eq!(*order.lock().unwrap(), ["early", "late", "none"]);
+    }
+
+    #[test]
+    fn test_saturated_background_does_not_starve_high() {
+        let pool = TaskPoolBuilder::new().num_threads(4).build();
+        let release = Arc::new(AtomicBool::new(false));
+        let started = Arc::new(AtomicI32::new(0));
+
+        // more blocking background tasks than threads
+        let background: Vec<_> = (0..8)
+            .map(|_| {
+                let release = release.clone();
+                let started = started.clone();
+                pool.spawn_with_priority(TaskPriority::Background, async move {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    while !release.load(Ordering::Acquire) {
+                        thread::sleep(core::time::Duration::from_millis(1));
+                    }
+                })
+            })
+            .collect();
+        // wait for every thread allowed to run them to be blocked
+        while started.load(Ordering::SeqCst) < 3 {
+            thread::yield_now();
+        }
+        thread::sleep(core::time::Duration::from_millis(50));
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        pool.spawn_with_priority(TaskPriority::High, async move {
+            done_tx.send(()).unwrap();
+        })
+        .detach();
+        let high_ran = done_rx.recv_timeout(core::time::Duration::from_secs(5));
+
+        release.store(true, Ordering::Release);
+        for task in background {
+            block_on(task);
+        }
+        assert!(high_ran.is_ok(), "high priority task starved by background tasks");
+    }
+
+    #[test]
+    fn test_scope_priorities_without_threads() {
+        // the scope thread must drive every priority when the pool has no threads
+        let pool = TaskPoolBuilder::new().num_threads(0).build();
+        let x = 1;
+        let x = &x;
+
+        let mut outputs = pool.scope(|scope| {
+            scope.spawn_with_priority(TaskPriority::Background, async { *x });
+            scope.spawn_with_priority(TaskPriority::High, async { *x + 1 });
+            scope.spawn_with_deadline(TaskPriority::Normal, Instant::now(), async { *x + 2 });
+        });
+
+        outputs.sort_unstable();
+        assert_eq!(outputs, [1, 2, 3]);
+    }
 }
@@ -0,0 +1,32 @@
+/// How urgently a task spawned on a [`TaskPool`](crate::TaskPool) should run.
+///
+/// Worker threads prefer ready [`High`](TaskPriority::High) tasks over
+/// [`Normal`](TaskPriority::Normal) ones, and those over
+/// [`Background`](TaskPriority::Background) ones. Each priority is run in batches, so a
+/// more urgent task waits at most for the current batch of less urgent ones. Within a
+/// priority, tasks spawned with a deadline run first, earliest deadline first.
+///
+/// Since a task holds its thread for as long as it is polled, some threads of the
+/// multi-threaded pool never run background tasks, see `TaskPoolBuilder::reserved_threads`.
+///
+/// Use [`High`](TaskPriority::High) for frame-critical work, e.g. the systems needed to
+/// deliver the next server tick, and [`Background`](TaskPriority::Background) for work that
+/// may span several frames, e.g. a pathfinding batch, so that it cannot starve the former.
+///
+/// The single-threaded task pool runs tasks in spawn order and ignores priorities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaskPriority {
+    /// Frame-critical work, run before anything else.
+    High,
+    /// The priority of tasks spawned without one.
+    #[default]
+    Normal,
+    /// Work that can be delayed until the pool is otherwise idle.
+    Background,
+}
+
+impl TaskPriority {
+    /// All priorities, from the most to the least urgent.
+    pub const ALL: [TaskPriority; 3] =
+        [TaskPriority::High, TaskPriority::Normal, TaskPriority::Background];
+}
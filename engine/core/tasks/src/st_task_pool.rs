@@ -1,8 +1,8 @@
 use alloc::{string::String, vec::Vec};
 use core::{cell::RefCell, future::Future, marker::PhantomData, mem};
-use obel_platform::sync::Arc;
+use obel_platform::{sync::Arc, time::Instant};
 
-use crate::Task;
+use crate::{Task, TaskPriority};
 
 #[cfg(feature = "std")]
 use std::thread_local;
@@ -222,6 +222,33 @@ impl TaskPool {
         }
     }
 
+    /// Spawns a static future onto the thread pool. On the single threaded task pool, the
+    /// priority is ignored and this is exactly the same as [`TaskPool::spawn`].
+    pub fn spawn_with_priority<T>(
+        &self,
+        _priority: TaskPriority,
+        future: impl Future<Output = T> + 'static + MaybeSend + MaybeSync,
+    ) -> Task<T>
+    where
+        T: 'static + MaybeSend + MaybeSync,
+    {
+        self.spawn(future)
+    }
+
+    /// Spawns a static future onto the thread pool. On the single threaded task pool, the
+    /// priority and deadline are ignored and this is exactly the same as [`TaskPool::spawn`].
+    pub fn spawn_with_deadline<T>(
+        &self,
+        _priority: TaskPriority,
+        _deadline: Instant,
+        future: impl Future<Output = T> + 'static + MaybeSend + MaybeSync,
+    ) -> Task<T>
+    where
+        T: 'static + MaybeSend + MaybeSync,
+    {
+        self.spawn(future)
+    }
+
     /// Spawns a static future on the JS event loop. This is exactly the same as [`TaskPool::spawn`].
     pub fn spawn_local<T>(
         &self,
@@ -282,6 +309,31 @@ impl<'scope, 'env, T: Send + 'env> Scope<'scope, 'env, T> {
         self.spawn_on_scope(f);
     }
 
+    /// Spawns a scoped future onto the executor. On the single threaded task pool, the
+    /// priority is ignored and it just calls [`Scope::spawn_on_scope`].
+    ///
+    /// For more information, see [`TaskPool::scope`].
+    pub fn spawn_with_priority<Fut: Future<Output = T> + 'scope + MaybeSend>(
+        &self,
+        _priority: TaskPriority,
+        f: Fut,
+    ) {
+        self.spawn_on_scope(f);
+    }
+
+    /// Spawns a scoped future onto the executor. On the single threaded task pool, the
+    /// priority and deadline are ignored and it just calls [`Scope::spawn_on_scope`].
+    ///
+    /// For more information, see [`TaskPool::scope`].
+    pub fn spawn_with_deadline<Fut: Future<Output = T> + 'scope + MaybeSend>(
+        &self,
+        _priority: TaskPriority,
+        _deadline: Instant,
+        f: Fut,
+    ) {
+        self.spawn_on_scope(f);
+    }
+
     /// Spawns a scoped future onto the executor. The scope *must* outlive
     /// the provided future. The results of the future will be returned as a part of
     /// [`TaskPool::scope`]'s return value.
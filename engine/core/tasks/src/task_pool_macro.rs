@@ -76,7 +76,8 @@ taskpool! {
 }
 
 /// A function used by `obel_app` to tick the global tasks pools on the main thread.
-/// This will run a maximum of 100 local tasks per executor per call to this function.
+/// This will run a maximum of 100 local tasks per executor per call to this function, then fire
+/// the [global timers](crate::Timers::global) that are due.
 ///
 /// # Warning
 ///
@@ -94,4 +95,5 @@ pub fn tick_global_task_pools_on_main_thread() {
             });
         });
     });
+    crate::timer::process_global_timers();
 }
@@ -0,0 +1,811 @@
+//! Async timers, see [`Timers`].
+
+use alloc::vec::Vec;
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use futures_lite::{FutureExt, future::poll_fn};
+#[cfg(any(
+    all(feature = "std", not(target_arch = "wasm32")),
+    all(target_arch = "wasm32", feature = "web")
+))]
+use obel_platform::sync::atomic::{AtomicBool, Ordering};
+use obel_platform::{
+    sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, Weak},
+    time::Instant,
+};
+
+/// The resolution of the timer wheel, in nanoseconds.
+const TICK_NANOS: u64 = 1_000_000;
+
+/// The number of slots of the timer wheel. Timers further than this many ticks away wait in
+/// their slot for as many rotations as needed.
+const SLOTS: u64 = 256;
+
+static GLOBAL_TIMERS: OnceLock<Timers> = OnceLock::new();
+
+/// Converts a time to the first wheel tick at or after it.
+fn tick_at_or_after(time: Duration) -> u64 {
+    u64::try_from(time.as_nanos().div_ceil(TICK_NANOS.into())).unwrap_or(u64::MAX)
+}
+
+/// Converts a time to the last wheel tick at or before it.
+fn tick_at_or_before(time: Duration) -> u64 {
+    u64::try_from(time.as_nanos() / u128::from(TICK_NANOS)).unwrap_or(u64::MAX)
+}
+
+fn tick_time(tick: u64) -> Duration {
+    Duration::from_nanos(tick.saturating_mul(TICK_NANOS))
+}
+
+/// Identifies a timer registered in a [`Wheel`].
+#[derive(Debug, Clone, Copy)]
+struct TimerKey {
+    id: u64,
+    tick: u64,
+}
+
+struct Entry {
+    id: u64,
+    tick: u64,
+    waker: Waker,
+}
+
+/// A hashed timer wheel: timers are stored in the slot of their tick modulo [`SLOTS`], so that
+/// registering, cancelling and expiring a timer only touches a single slot.
+struct Wheel {
+    slots: Vec<Vec<Entry>>,
+    /// The first tick that has not been expired yet.
+    tick: u64,
+    next_id: u64,
+    len: usize,
+}
+
+impl Wheel {
+    fn new() -> Self {
+        Self {
+            slots: (0..SLOTS).map(|_| Vec::new()).collect(),
+            tick: 0,
+            next_id: 0,
+            len: 0,
+        }
+    }
+
+    fn slot(&mut self, tick: u64) -> &mut Vec<Entry> {
+        // the remainder is below `SLOTS`, so it fits in a `usize`
+        &mut self.slots[(tick % SLOTS) as usize]
+    }
+
+    fn insert(&mut self, deadline: Duration, waker: Waker) -> TimerKey {
+        // a timer whose tick has already been expired fires on the next expiry
+        let tick = tick_at_or_after(deadline).max(self.tick);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.len += 1;
+        self.slot(tick).push(Entry {
+            id,
+            tick,
+            waker,
+        });
+        TimerKey {
+            id,
+            tick,
+        }
+    }
+
+    /// Replaces the waker of a timer, returning `false` if it has already fired.
+    fn update(&mut self, key: TimerKey, waker: &Waker) -> bool {
+        match self.slot(key.tick).iter_mut().find(|entry| entry.id == key.id) {
+            Some(entry) => {
+                if !entry.waker.will_wake(waker) {
+                    entry.waker.clone_from(waker);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove(&mut self, key: TimerKey) {
+        let slot = self.slot(key.tick);
+        if let Some(index) = slot.iter().position(|entry| entry.id == key.id) {
+            slot.swap_remove(index);
+            self.len -= 1;
+        }
+    }
+
+    /// Removes the timers due at `now`, returning their wakers.
+    fn expire(&mut self, now: Duration) -> Vec<Waker> {
+        let now_tick = tick_at_or_before(now);
+        let mut wakers = Vec::new();
+        if now_tick < self.tick {
+            return wakers;
+        }
+        // after a long gap, every slot is visited once instead of once per elapsed tick
+        let visited = (now_tick - self.tick).saturating_add(1).min(SLOTS);
+        for tick in self.tick..self.tick + visited {
+            let slot = self.slot(tick);
+            let mut index = 0;
+            while index < slot.len() {
+                if slot[index].tick <= now_tick {
+                    wakers.push(slot.swap_remove(index).waker);
+                } else {
+                    index += 1;
+                }
+            }
+        }
+        self.len -= wakers.len();
+        self.tick = now_tick + 1;
+        wakers
+    }
+
+    /// Returns the tick at which the wheel should next be expired: the tick of the earliest
+    /// timer, or one rotation ahead if every timer is further away.
+    fn next_tick(&self) -> Option<u64> {
+        if self.len == 0 {
+            return None;
+        }
+        // slots are visited in tick order, so the first timer due in this rotation is the earliest
+        let next = (self.tick..self.tick + SLOTS).find(|&tick| {
+            self.slots[(tick % SLOTS) as usize].iter().any(|entry| entry.tick == tick)
+        });
+        // `self.tick - 1` is the last expired tick, i.e. now
+        Some(next.unwrap_or(self.tick.saturating_sub(1) + SLOTS))
+    }
+}
+
+enum Clock {
+    Real(Instant),
+    Mock(MockClock),
+}
+
+struct TimersState {
+    clock: Clock,
+    wheel: Mutex<Wheel>,
+    /// Whether these timers have been handed to the driver.
+    #[cfg(any(
+        all(feature = "std", not(target_arch = "wasm32")),
+        all(target_arch = "wasm32", feature = "web")
+    ))]
+    driven: AtomicBool,
+}
+
+impl TimersState {
+    fn now(&self) -> Duration {
+        match &self.clock {
+            Clock::Real(start) => Instant::now().saturating_duration_since(*start),
+            Clock::Mock(clock) => clock.elapsed(),
+        }
+    }
+
+    fn wheel(&self) -> MutexGuard<'_, Wheel> {
+        self.wheel.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn process(&self) -> Option<Duration> {
+        let now = self.now();
+        let mut wheel = self.wheel();
+        let wakers = wheel.expire(now);
+        let next_tick = wheel.next_tick();
+        drop(wheel);
+        for waker in wakers {
+            waker.wake();
+        }
+        next_tick.map(|tick| tick_time(tick).saturating_sub(now))
+    }
+}
+
+/// A set of timers backing the [`Sleep`], [`Interval`] and [`timeout`](Timers::timeout)
+/// futures.
+///
+/// Timers are stored in a hashed timer wheel with a resolution of one millisecond, and fire
+/// when the wheel is serviced:
+///
+/// - With the real clock, a single driver shared by all timers services the wheels: a
+///   background thread on native targets, and a task woken by `setTimeout` on the web. It
+///   starts with the first timer.
+/// - Elsewhere, e.g. on `no_std` targets, `tick_global_task_pools_on_main_thread` services the
+///   [global](Self::global) timers every frame, and other timers must be serviced manually
+///   with [`process`](Self::process).
+/// - With a [`MockClock`], the wheel is serviced every time the clock is
+///   [advanced](MockClock::advance), so tests control exactly when timers fire.
+///
+/// Most code uses the global timers through [`sleep`], [`interval`] and [`timeout`].
+///
+/// ```
+/// # use core::time::Duration;
+/// # use obel_tasks::{MockClock, Timers, poll_once, block_on};
+/// let clock = MockClock::new();
+/// let timers = Timers::with_mock_clock(&clock);
+///
+/// // e.g. a statechart `after(2.6s)` transition
+/// let mut after = Box::pin(timers.sleep(Duration::from_secs_f32(2.6)));
+/// assert!(block_on(poll_once(&mut after)).is_none());
+///
+/// clock.advance(Duration::from_secs(3));
+/// assert!(block_on(poll_once(&mut after)).is_some());
+/// ```
+#[derive(Clone)]
+pub struct Timers {
+    state: Arc<TimersState>,
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timers {
+    /// Creates timers using the real clock.
+    pub fn new() -> Self {
+        Self::with_clock(Clock::Real(Instant::now()))
+    }
+
+    /// Creates timers using `clock`, which fires them when advanced.
+    pub fn with_mock_clock(clock: &MockClock) -> Self {
+        let timers = Self::with_clock(Clock::Mock(clock.clone()));
+        clock.state().timers.push(Arc::downgrade(&timers.state));
+        timers
+    }
+
+    fn with_clock(clock: Clock) -> Self {
+        Self {
+            state: Arc::new(TimersState {
+                clock,
+                wheel: Mutex::new(Wheel::new()),
+                #[cfg(any(
+                    all(feature = "std", not(target_arch = "wasm32")),
+                    all(target_arch = "wasm32", feature = "web")
+                ))]
+                driven: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Returns the global timers, using the real clock.
+    pub fn global() -> &'static Timers {
+        GLOBAL_TIMERS.get_or_init(Timers::new)
+    }
+
+    /// Returns the time elapsed on the clock of these timers since they were created, or since
+    /// the [`MockClock`] was created.
+    pub fn now(&self) -> Duration {
+        self.state.now()
+    }
+
+    /// Returns a future completing once `duration` has elapsed.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        self.sleep_until(self.now().saturating_add(duration))
+    }
+
+    /// Returns a future completing once [`now`](Self::now) reaches `deadline`.
+    pub fn sleep_until(&self, deadline: Duration) -> Sleep {
+        Sleep {
+            timers: self.clone(),
+            deadline,
+            key: None,
+        }
+    }
+
+    /// Returns an [`Interval`] ticking every `period`, starting one `period` from now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn interval(&self, period: Duration) -> Interval {
+        assert!(!period.is_zero(), "the period of an interval must be greater than zero");
+        Interval {
+            sleep: self.sleep(period),
+            period,
+        }
+    }
+
+    /// Runs `future` until it completes, returning its output, or until `duration` has elapsed,
+    /// returning [`TimedOut`]. The future is dropped when it times out.
+    ///
+    /// A future completing at the same time as it times out is not considered to time out.
+    pub fn timeout<F: Future>(
+        &self,
+        duration: Duration,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, TimedOut>> + use<F> {
+        let sleep = self.sleep(duration);
+        async move {
+            async { Ok(future.await) }
+                .or(async {
+                    sleep.await;
+                    Err(TimedOut)
+                })
+                .await
+        }
+    }
+
+    /// Fires the timers that are due and returns the time left until the wheel should be
+    /// serviced again: until the next timer, but at most one rotation of the wheel. Returns
+    /// `None` if no timer is waiting.
+    pub fn process(&self) -> Option<Duration> {
+        self.state.process()
+    }
+
+    /// Returns the number of timers waiting to fire.
+    pub fn len(&self) -> usize {
+        self.state.wheel().len
+    }
+
+    /// Returns `true` if no timer is waiting to fire.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Makes sure the wheel gets serviced after a timer was registered.
+    fn notify(&self) {
+        #[cfg(any(
+            all(feature = "std", not(target_arch = "wasm32")),
+            all(target_arch = "wasm32", feature = "web")
+        ))]
+        if matches!(self.state.clock, Clock::Real(_)) {
+            if !self.state.driven.swap(true, Ordering::AcqRel) {
+                driven_timers().push(Arc::downgrade(&self.state));
+            }
+            driver::wake();
+        }
+    }
+}
+
+impl fmt::Debug for Timers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timers")
+            .field("now", &self.now())
+            .field("len", &self.len())
+            .field("mock", &matches!(self.state.clock, Clock::Mock(_)))
+            .finish()
+    }
+}
+
+/// The real clock timers serviced by the driver.
+#[cfg(any(
+    all(feature = "std", not(target_arch = "wasm32")),
+    all(target_arch = "wasm32", feature = "web")
+))]
+fn driven_timers() -> MutexGuard<'static, Vec<Weak<TimersState>>> {
+    static DRIVEN_TIMERS: OnceLock<Mutex<Vec<Weak<TimersState>>>> = OnceLock::new();
+    DRIVEN_TIMERS.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Services the timers handed to the driver, returning the time left until they should be
+/// serviced again.
+#[cfg(any(
+    all(feature = "std", not(target_arch = "wasm32")),
+    all(target_arch = "wasm32", feature = "web")
+))]
+fn service_driven_timers() -> Option<Duration> {
+    let mut driven = driven_timers();
+    driven.retain(|timers| timers.strong_count() > 0);
+    let timers: Vec<_> = driven.iter().filter_map(Weak::upgrade).collect();
+    drop(driven);
+    timers.iter().filter_map(|timers| timers.process()).min()
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+mod driver {
+    use super::service_driven_timers;
+    use obel_platform::sync::OnceLock;
+    use std::thread::{self, Thread};
+
+    /// Wakes the thread servicing real clock timers, spawning it first if needed.
+    pub(super) fn wake() {
+        static THREAD: OnceLock<Thread> = OnceLock::new();
+        THREAD
+            .get_or_init(|| {
+                thread::Builder::new()
+                    .name("obel-timer".into())
+                    .spawn(|| {
+                        loop {
+                            match service_driven_timers() {
+                                Some(timeout) => thread::park_timeout(timeout),
+                                None => thread::park(),
+                            }
+                        }
+                    })
+                    .expect("failed to spawn the timer thread")
+                    .thread()
+                    .clone()
+            })
+            .unpark();
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+mod driver {
+    use super::service_driven_timers;
+    use atomic_waker::AtomicWaker;
+    use core::{task::Poll, time::Duration};
+    use futures_lite::{FutureExt, future::poll_fn};
+    use obel_platform::sync::atomic::{AtomicBool, Ordering};
+    use wasm_bindgen_futures::{
+        JsFuture, js_sys,
+        wasm_bindgen::{JsCast, JsValue},
+    };
+
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    static NOTIFIED: AtomicBool = AtomicBool::new(false);
+    static WAKER: AtomicWaker = AtomicWaker::new();
+
+    /// Wakes the task servicing real clock timers, spawning it first if needed.
+    pub(super) fn wake() {
+        NOTIFIED.store(true, Ordering::Release);
+        if STARTED.swap(true, Ordering::AcqRel) {
+            WAKER.wake();
+        } else {
+            wasm_bindgen_futures::spawn_local(drive());
+        }
+    }
+
+    async fn drive() {
+        loop {
+            let next = service_driven_timers();
+            let notified = poll_fn(|cx| {
+                WAKER.register(cx.waker());
+                if NOTIFIED.swap(false, Ordering::AcqRel) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            });
+            match next {
+                Some(timeout) => {
+                    notified
+                        .or(async {
+                            // a rejected promise wakes the driver all the same
+                            let _ = set_timeout(timeout).await;
+                        })
+                        .await;
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// Returns a future resolved by `setTimeout` after `timeout`.
+    fn set_timeout(timeout: Duration) -> JsFuture {
+        let millis = i32::try_from(timeout.as_nanos().div_ceil(1_000_000)).unwrap_or(i32::MAX);
+        let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+            // `setTimeout` is a global of both windows and workers
+            let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+                .expect("`setTimeout` is not available")
+                .unchecked_into::<js_sys::Function>();
+            let _ = set_timeout.call2(&JsValue::UNDEFINED, &resolve, &millis.into());
+        });
+        JsFuture::from(promise)
+    }
+}
+
+/// Services the global timers, if they have been used.
+#[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+pub(crate) fn process_global_timers() {
+    if let Some(timers) = GLOBAL_TIMERS.get() {
+        timers.process();
+    }
+}
+
+/// A clock that only moves when told to, for testing code using [`Timers`].
+///
+/// Clones share the same time.
+#[derive(Clone, Default)]
+pub struct MockClock {
+    state: Arc<Mutex<MockClockState>>,
+}
+
+#[derive(Default)]
+struct MockClockState {
+    elapsed: Duration,
+    timers: Vec<Weak<TimersState>>,
+}
+
+impl MockClock {
+    /// Creates a clock at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the time elapsed on this clock.
+    pub fn elapsed(&self) -> Duration {
+        self.state().elapsed
+    }
+
+    /// Moves this clock forward by `duration`, firing the timers that are then due.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state();
+        state.elapsed = state.elapsed.saturating_add(duration);
+        state.timers.retain(|timers| timers.strong_count() > 0);
+        let timers: Vec<_> = state.timers.iter().filter_map(Weak::upgrade).collect();
+        // servicing the timers reads the clock again
+        drop(state);
+        for timers in timers {
+            timers.process();
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockClockState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for MockClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockClock").field("elapsed", &self.elapsed()).finish()
+    }
+}
+
+/// The future returned by [`sleep`] and [`Timers::sleep`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    timers: Timers,
+    deadline: Duration,
+    key: Option<TimerKey>,
+}
+
+impl Sleep {
+    /// Returns the time at which this future completes, on the clock of its [`Timers`].
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+
+    /// Changes the time at which this future completes.
+    pub fn reset(&mut self, deadline: Duration) {
+        self.cancel();
+        self.deadline = deadline;
+    }
+
+    fn cancel(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.timers.state.wheel().remove(key);
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.timers.now() >= self.deadline {
+            self.cancel();
+            return Poll::Ready(());
+        }
+        let mut wheel = self.timers.state.wheel();
+        let registered = match self.key {
+            Some(key) => wheel.update(key, cx.waker()),
+            None => false,
+        };
+        if !registered {
+            let key = wheel.insert(self.deadline, cx.waker().clone());
+            drop(wheel);
+            self.key = Some(key);
+            self.timers.notify();
+        } else {
+            drop(wheel);
+        }
+        // the deadline may have passed while the timer was registered
+        if self.timers.now() >= self.deadline {
+            self.cancel();
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Ticks at a fixed period, see [`interval`] and [`Timers::interval`].
+///
+/// If ticks are missed, e.g. because the task awaiting them was busy, the next tick happens
+/// right away and the following one a full period later: missed ticks are skipped rather than
+/// delivered in a burst.
+#[derive(Debug)]
+pub struct Interval {
+    sleep: Sleep,
+    period: Duration,
+}
+
+impl Interval {
+    /// Returns the period of this interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Waits for the next tick.
+    pub async fn tick(&mut self) {
+        poll_fn(|cx| self.poll_tick(cx)).await;
+    }
+
+    /// Polls for the next tick.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if Pin::new(&mut self.sleep).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let now = self.sleep.timers.now();
+        let mut next = self.sleep.deadline.saturating_add(self.period);
+        if next <= now {
+            next = now.saturating_add(self.period);
+        }
+        self.sleep.reset(next);
+        Poll::Ready(())
+    }
+}
+
+/// The error returned when a future passed to [`timeout`] or [`Timers::timeout`] times out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("future timed out")
+    }
+}
+
+impl core::error::Error for TimedOut {}
+
+/// Returns a future completing once `duration` has elapsed, using the [global](Timers::global)
+/// timers.
+///
+/// ```
+/// # use core::time::Duration;
+/// # use obel_tasks::{block_on, sleep};
+/// block_on(sleep(Duration::from_millis(5)));
+/// ```
+pub fn sleep(duration: Duration) -> Sleep {
+    Timers::global().sleep(duration)
+}
+
+/// Returns an [`Interval`] ticking every `period` using the [global](Timers::global) timers,
+/// starting one `period` from now.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn interval(period: Duration) -> Interval {
+    Timers::global().interval(period)
+}
+
+/// Runs `future` for at most `duration` using the [global](Timers::global) timers, see
+/// [`Timers::timeout`].
+///
+/// ```
+/// # use core::time::Duration;
+/// # use futures_lite::future;
+/// # use obel_tasks::{TimedOut, block_on, timeout};
+/// let retry = timeout(Duration::from_millis(5), future::pending::<()>());
+/// assert_eq!(block_on(retry), Err(TimedOut));
+/// ```
+pub fn timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> impl Future<Output = Result<F::Output, TimedOut>> {
+    Timers::global().timeout(duration, future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_on;
+    use alloc::boxed::Box;
+    use futures_lite::future;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn sleep_fires_when_mock_clock_reaches_deadline() {
+        let clock = MockClock::new();
+        let timers = Timers::with_mock_clock(&clock);
+        let mut sleep = Box::pin(timers.sleep(millis(2600)));
+
+        assert!(block_on(future::poll_once(&mut sleep)).is_none());
+        assert_eq!(timers.len(), 1);
+
+        clock.advance(millis(2599));
+        assert!(block_on(future::poll_once(&mut sleep)).is_none());
+
+        clock.advance(millis(1));
+        assert!(timers.is_empty());
+        assert!(block_on(future::poll_once(&mut sleep)).is_some());
+    }
+
+    #[test]
+    fn timers_beyond_one_rotation_wait_for_their_round() {
+        let clock = MockClock::new();
+        let timers = Timers::with_mock_clock(&clock);
+        let mut far = Box::pin(timers.sleep(millis(SLOTS + 10)));
+        let mut near = Box::pin(timers.sleep(millis(10)));
+        assert!(block_on(future::poll_once(&mut far)).is_none());
+        assert!(block_on(future::poll_once(&mut near)).is_none());
+
+        clock.advance(millis(10));
+        assert_eq!(timers.len(), 1);
+        assert_eq!(timers.process(), Some(millis(SLOTS)));
+
+        // a long gap visits every slot once
+        clock.advance(millis(10 * SLOTS));
+        assert!(timers.is_empty());
+        assert!(block_on(future::poll_once(&mut far)).is_some());
+    }
+
+    #[test]
+    fn far_timers_are_serviced_once_per_rotation() {
+        let clock = MockClock::new();
+        let timers = Timers::with_mock_clock(&clock);
+        let mut far = Box::pin(timers.sleep(millis(10 * SLOTS)));
+        assert!(block_on(future::poll_once(&mut far)).is_none());
+        assert_eq!(timers.process(), Some(millis(SLOTS)));
+
+        clock.advance(millis(9 * SLOTS + 5));
+        assert_eq!(timers.process(), Some(millis(SLOTS - 5)));
+    }
+
+    #[test]
+    fn dropping_sleep_cancels_timer() {
+        let clock = MockClock::new();
+        let timers = Timers::with_mock_clock(&clock);
+        let mut sleep = Box::pin(timers.sleep(millis(5)));
+        assert!(block_on(future::poll_once(&mut sleep)).is_none());
+        drop(sleep);
+        assert!(timers.is_empty());
+        assert_eq!(timers.process(), None);
+    }
+
+    #[test]
+    fn interval_skips_missed_ticks() {
+        let clock = MockClock::new();
+        let timers = Timers::with_mock_clock(&clock);
+        let mut interval = timers.interval(millis(100));
+        let mut poll_tick = || block_on(future::poll_once(interval.tick())).is_some();
+
+        assert!(!poll_tick());
+        clock.advance(millis(100));
+        assert!(poll_tick());
+        assert!(!poll_tick());
+
+        clock.advance(millis(350));
+        assert!(poll_tick());
+        assert!(!poll_tick());
+        clock.advance(millis(99));
+        assert!(!poll_tick());
+        clock.advance(millis(1));
+        assert!(poll_tick());
+    }
+
+    #[test]
+    fn timeout_prefers_completed_future() {
+        let clock = MockClock::new();
+        let timers = Timers::with_mock_clock(&clock);
+
+        assert_eq!(block_on(timers.timeout(Duration::ZERO, async { 1 })), Ok(1));
+
+        let mut pending = Box::pin(timers.timeout(millis(50), future::pending::<()>()));
+        assert!(block_on(future::poll_once(&mut pending)).is_none());
+        clock.advance(millis(50));
+        assert_eq!(block_on(pending), Err(TimedOut));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn real_clock_timers_are_driven() {
+        let timers = Timers::new();
+        let start = timers.now();
+        block_on(timers.sleep(millis(20)));
+        assert!(timers.now() - start >= millis(20));
+        assert_eq!(block_on(timers.timeout(millis(5), future::pending::<()>())), Err(TimedOut));
+    }
+}
@@ -0,0 +1,414 @@
+use core::time::Duration;
+use log::debug;
+
+use crate::{time::Time, virt::Virtual};
+
+/// The fixed timestep game clock following virtual time.
+///
+/// A specialization of the [`Time`] structure. **For method documentation, see
+/// [`Time<Fixed>#impl-Time<Fixed>`].**
+///
+/// It is normally driven by [`run_fixed_steps`], once per frame, after
+/// [`update_virtual_time`](crate::update_virtual_time). After each frame, fixed
+/// time is less than one [`timestep`](Time::timestep) behind virtual time, not
+/// counting timesteps skipped after running the maximum number of steps.
+///
+/// Fixed time advances in fixed-size increments, which is useful for
+/// simulation and other logic that must stay deterministic regardless of the
+/// frame rate, such as the server tick. The default timestep is 64 Hz, or
+/// 15625 microseconds, which is representable exactly in both [`f32`] and
+/// [`f64`] seconds.
+///
+/// Each frame, the [`delta()`](Time::delta) of [`Time<Virtual>`](crate::Virtual)
+/// is accumulated into an [`overstep()`](Time::overstep), and for as long as
+/// the overstep is at least one [`timestep()`](Time::timestep), the clock
+/// advances by one timestep and the fixed step runs. This may happen zero, one
+/// or more times per frame. Since virtual time honors pause and relative speed,
+/// fixed steps stop while the game is paused and run more often when it is sped
+/// up.
+///
+/// [`Time::<Virtual>::max_delta`] bounds the real time a frame can add before
+/// relative speed is applied, so it does not bound the number of steps on its
+/// own. At most [`max_steps_per_frame()`](Time::max_steps_per_frame) steps run
+/// in one frame; when a frame hits that limit, the whole timesteps still left
+/// in the overstep are dropped, so that a lagging game slows down instead of
+/// falling further behind every frame.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed {
+    timestep: Duration,
+    overstep: Duration,
+    max_steps_per_frame: u32,
+}
+
+impl Time<Fixed> {
+    /// Corresponds to 64 Hz.
+    const DEFAULT_TIMESTEP: Duration = Duration::from_micros(15625);
+
+    /// The default for [`max_steps_per_frame`](Self::max_steps_per_frame).
+    const DEFAULT_MAX_STEPS_PER_FRAME: u32 = 8;
+
+    /// Return new fixed time clock with given timestep as [`Duration`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timestep` is zero.
+    pub fn from_duration(timestep: Duration) -> Self {
+        let mut ret = Self::default();
+        ret.set_timestep(timestep);
+        ret
+    }
+
+    /// Return new fixed time clock with given timestep seconds as `f64`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seconds` is zero, negative or not finite.
+    pub fn from_seconds(seconds: f64) -> Self {
+        let mut ret = Self::default();
+        ret.set_timestep_seconds(seconds);
+        ret
+    }
+
+    /// Return new fixed time clock with given timestep frequency in Hertz (1/seconds)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero, negative or not finite.
+    pub fn from_hz(hz: f64) -> Self {
+        let mut ret = Self::default();
+        ret.set_timestep_hz(hz);
+        ret
+    }
+
+    /// Returns the amount of virtual time that must pass before the fixed
+    /// timestep schedule is run again.
+    #[inline]
+    pub fn timestep(&self) -> Duration {
+        self.context().timestep
+    }
+
+    /// Sets the amount of virtual time that must pass before the fixed timestep
+    /// schedule is run again, as [`Duration`].
+    ///
+    /// Takes effect from the next time the fixed timestep schedule is run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timestep` is zero.
+    #[inline]
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        assert_ne!(timestep, Duration::ZERO, "attempted to set fixed timestep to zero");
+        self.context_mut().timestep = timestep;
+    }
+
+    /// Sets the amount of virtual time that must pass before the fixed timestep
+    /// schedule is run again, as seconds.
+    ///
+    /// Timestep is stored as a [`Duration`], which has fixed nanosecond
+    /// resolution and will be converted from the floating point number.
+    ///
+    /// Takes effect from the next time the fixed timestep schedule is run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seconds` is zero, negative or not finite.
+    #[inline]
+    pub fn set_timestep_seconds(&mut self, seconds: f64) {
+        assert!(seconds.is_sign_positive(), "seconds less than or equal to zero");
+        assert!(seconds.is_finite(), "seconds is infinite");
+        self.set_timestep(Duration::from_secs_f64(seconds));
+    }
+
+    /// Sets the amount of virtual time that must pass before the fixed timestep
+    /// schedule is run again, as frequency.
+    ///
+    /// The timestep value is set to `1 / hz`, converted to a [`Duration`] which
+    /// has fixed nanosecond resolution.
+    ///
+    /// Takes effect from the next time the fixed timestep schedule is run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero, negative or not finite.
+    #[inline]
+    pub fn set_timestep_hz(&mut self, hz: f64) {
+        assert!(hz.is_sign_positive(), "Hz less than or equal to zero");
+        assert!(hz.is_finite(), "Hz is infinite");
+        self.set_timestep_seconds(1.0 / hz);
+    }
+
+    /// Returns the maximum number of steps [`run_fixed_steps`] runs in one frame.
+    #[inline]
+    pub fn max_steps_per_frame(&self) -> u32 {
+        self.context().max_steps_per_frame
+    }
+
+    /// Sets the maximum number of steps [`run_fixed_steps`] runs in one frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_steps` is zero.
+    #[inline]
+    pub fn set_max_steps_per_frame(&mut self, max_steps: u32) {
+        assert_ne!(max_steps, 0, "attempted to set max steps per frame to zero");
+        self.context_mut().max_steps_per_frame = max_steps;
+    }
+
+    /// Returns the amount of overstep time accumulated toward new steps, as
+    /// [`Duration`].
+    #[inline]
+    pub fn overstep(&self) -> Duration {
+        self.context().overstep
+    }
+
+    /// Discard a part of the overstep amount.
+    ///
+    /// If `discard` is higher than overstep, the overstep becomes zero.
+    #[inline]
+    pub fn discard_overstep(&mut self, discard: Duration) {
+        let context = self.context_mut();
+        context.overstep = context.overstep.saturating_sub(discard);
+    }
+
+    /// Returns the amount of overstep time accumulated toward new steps, as an
+    /// [`f32`] fraction of the timestep.
+    #[inline]
+    pub fn overstep_fraction(&self) -> f32 {
+        self.context().overstep.as_secs_f32() / self.context().timestep.as_secs_f32()
+    }
+
+    /// Returns the amount of overstep time accumulated toward new steps, as an
+    /// [`f64`] fraction of the timestep.
+    #[inline]
+    pub fn overstep_fraction_f64(&self) -> f64 {
+        self.context().overstep.as_secs_f64() / self.context().timestep.as_secs_f64()
+    }
+
+    /// Adds `delta` to the accumulated overstep.
+    pub fn accumulate(&mut self, delta: Duration) {
+        self.context_mut().overstep += delta;
+    }
+
+    /// Consumes one timestep from the overstep and advances the clock by it.
+    ///
+    /// Returns `false`, leaving the clock untouched, if less than one timestep has been
+    /// accumulated.
+    pub fn expend(&mut self) -> bool {
+        let timestep = self.timestep();
+        if let Some(new_value) = self.context().overstep.checked_sub(timestep) {
+            // reduce accumulated and increase elapsed by period
+            self.context_mut().overstep = new_value;
+            self.advance_by(timestep);
+            true
+        } else {
+            // no more periods left in accumulated
+            false
+        }
+    }
+}
+
+impl Default for Fixed {
+    fn default() -> Self {
+        Self {
+            timestep: Time::<Fixed>::DEFAULT_TIMESTEP,
+            overstep: Duration::ZERO,
+            max_steps_per_frame: Time::<Fixed>::DEFAULT_MAX_STEPS_PER_FRAME,
+        }
+    }
+}
+
+/// Runs `step` as many times as the accumulated virtual time allows.
+///
+/// Accumulates the [`delta()`](Time::delta) of `virt` into `fixed`, then calls `step` once
+/// per whole [`timestep`](Time::timestep) with `current` set to the fixed clock, up to
+/// [`max_steps_per_frame`](Time::max_steps_per_frame) times. Whole timesteps left over after
+/// the last allowed step are discarded. Afterwards `current` is set back to the virtual clock.
+/// Returns the number of steps run this frame.
+///
+/// This is the fixed update loop: call it once per frame after
+/// [`update_virtual_time`](crate::update_virtual_time).
+///
+/// ```
+/// # use obel_time::{run_fixed_steps, update_virtual_time, Fixed, Real, Time, Virtual};
+/// # use core::time::Duration;
+/// let mut real = Time::<Real>::default();
+/// let mut virt = Time::<Virtual>::default();
+/// let mut fixed = Time::<Fixed>::from_hz(64.0);
+/// let mut current = Time::default();
+///
+/// real.update_with_duration(Duration::ZERO);
+/// real.update_with_duration(Duration::from_millis(50));
+/// update_virtual_time(&mut current, &mut virt, &real);
+///
+/// // 50ms of virtual time hold three whole 15.625ms steps
+/// let steps = run_fixed_steps(&mut current, &virt, &mut fixed, |time| {
+///     assert_eq!(time.delta(), Duration::from_micros(15625));
+/// });
+/// assert_eq!(steps, 3);
+/// assert_eq!(fixed.overstep(), Duration::from_micros(3125));
+/// ```
+pub fn run_fixed_steps(
+    current: &mut Time,
+    virt: &Time<Virtual>,
+    fixed: &mut Time<Fixed>,
+    mut step: impl FnMut(&Time),
+) -> u32 {
+    fixed.accumulate(virt.delta());
+    let max_steps = fixed.max_steps_per_frame();
+    let mut steps = 0;
+    while steps < max_steps && fixed.expend() {
+        *current = fixed.as_generic();
+        step(current);
+        steps += 1;
+    }
+    let timestep = fixed.timestep();
+    let behind = fixed.overstep().as_nanos() / timestep.as_nanos();
+    if behind > 0 {
+        let skipped = timestep * u32::try_from(behind).unwrap_or(u32::MAX);
+        debug!("ran the maximum of {max_steps} fixed steps this frame, skipping {skipped:?}");
+        fixed.discard_overstep(skipped);
+    }
+    *current = virt.as_generic();
+    steps
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Real, update_virtual_time};
+
+    #[test]
+    fn test_set_timestep() {
+        let mut time = Time::<Fixed>::default();
+
+        assert_eq!(time.timestep(), Time::<Fixed>::DEFAULT_TIMESTEP);
+
+        time.set_timestep(Duration::from_millis(500));
+        assert_eq!(time.timestep(), Duration::from_millis(500));
+
+        time.set_timestep_seconds(0.25);
+        assert_eq!(time.timestep(), Duration::from_millis(250));
+
+        time.set_timestep_hz(8.0);
+        assert_eq!(time.timestep(), Duration::from_millis(125));
+    }
+
+    #[test]
+    fn test_expend() {
+        let mut time = Time::<Fixed>::from_seconds(2.0);
+
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.elapsed(), Duration::ZERO);
+
+        time.accumulate(Duration::from_secs(1));
+
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.elapsed(), Duration::ZERO);
+        assert_eq!(time.overstep(), Duration::from_secs(1));
+        assert_eq!(time.overstep_fraction(), 0.5);
+        assert_eq!(time.overstep_fraction_f64(), 0.5);
+
+        assert!(!time.expend()); // false
+
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.elapsed(), Duration::ZERO);
+        assert_eq!(time.overstep(), Duration::from_secs(1));
+
+        time.accumulate(Duration::from_secs(1));
+
+        assert_eq!(time.overstep(), Duration::from_secs(2));
+        assert_eq!(time.overstep_fraction(), 1.0);
+
+        assert!(time.expend()); // true
+
+        assert_eq!(time.delta(), Duration::from_secs(2));
+        assert_eq!(time.elapsed(), Duration::from_secs(2));
+        assert_eq!(time.overstep(), Duration::ZERO);
+
+        assert!(!time.expend()); // false
+
+        time.accumulate(Duration::from_secs(1));
+        time.discard_overstep(Duration::from_millis(500));
+
+        assert_eq!(time.overstep(), Duration::from_millis(500));
+
+        time.discard_overstep(Duration::from_secs(1));
+
+        assert_eq!(time.overstep(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_run_fixed_steps() {
+        let mut virt = Time::<Virtual>::default();
+        let mut fixed = Time::<Fixed>::from_duration(Duration::from_millis(10));
+        let mut current = Time::default();
+
+        virt.advance_by(Duration::from_millis(35));
+        let mut elapsed = [Duration::ZERO; 4];
+        let steps = run_fixed_steps(&mut current, &virt, &mut fixed, |time| {
+            let i = elapsed.iter().position(Duration::is_zero).unwrap();
+            elapsed[i] = time.elapsed();
+        });
+
+        assert_eq!(steps, 3);
+        assert_eq!(
+            elapsed,
+            [
+                Duration::from_millis(10),
+                Duration::from_millis(20),
+                Duration::from_millis(30),
+                Duration::ZERO
+            ]
+        );
+        assert_eq!(fixed.overstep(), Duration::from_millis(5));
+        assert_eq!(current.elapsed(), virt.elapsed());
+
+        // the remaining overstep carries over to the next frame
+        virt.advance_by(Duration::from_millis(5));
+        assert_eq!(run_fixed_steps(&mut current, &virt, &mut fixed, |_| {}), 1);
+        assert_eq!(fixed.elapsed(), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_run_fixed_steps_is_bounded_when_sped_up() {
+        let mut real = Time::<Real>::default();
+        let mut virt = Time::<Virtual>::default();
+        let mut fixed = Time::<Fixed>::from_duration(Duration::from_millis(10));
+        let mut current = Time::default();
+        fixed.set_max_steps_per_frame(4);
+
+        // 250ms of real time is clamped to `max_delta`, then sped up 100 times
+        virt.set_relative_speed_f64(100.0);
+        real.update_with_duration(Duration::ZERO);
+        real.update_with_duration(Duration::from_millis(250));
+        update_virtual_time(&mut current, &mut virt, &real);
+        assert!(virt.delta() > Duration::from_secs(20));
+
+        assert_eq!(run_fixed_steps(&mut current, &virt, &mut fixed, |_| {}), 4);
+        assert_eq!(fixed.elapsed(), Duration::from_millis(40));
+        assert!(fixed.overstep() < fixed.timestep());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to set max steps per frame to zero")]
+    fn test_zero_max_steps_panics() {
+        Time::<Fixed>::default().set_max_steps_per_frame(0);
+    }
+
+    #[test]
+    fn test_paused_virtual_time_runs_no_steps() {
+        let mut real = Time::<Real>::default();
+        let mut virt = Time::<Virtual>::default();
+        let mut fixed = Time::<Fixed>::default();
+        let mut current = Time::default();
+
+        virt.pause();
+        real.update_with_duration(Duration::ZERO);
+        real.update_with_duration(Duration::from_millis(100));
+        update_virtual_time(&mut current, &mut virt, &real);
+
+        assert_eq!(run_fixed_steps(&mut current, &virt, &mut fixed, |_| {}), 0);
+        assert_eq!(fixed.elapsed(), Duration::ZERO);
+    }
+}
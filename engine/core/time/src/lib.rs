@@ -0,0 +1,27 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+// #![doc(html_logo_url = "assets/icon.png", html_favicon_url = "assets/icon.png")]
+#![no_std] // tells the compiler "don't automatically link std"
+
+//! Clocks used to advance an Obel app: real, virtual and fixed-timestep time.
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod fixed;
+mod real;
+mod time;
+mod virt;
+
+pub use fixed::*;
+pub use real::*;
+pub use time::*;
+pub use virt::*;
+
+/// The time prelude.
+///
+/// This includes the most common types in this crate, re-exported for your convenience.
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{Fixed, Real, Time, Virtual};
+}
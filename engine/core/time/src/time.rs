@@ -0,0 +1,378 @@
+use core::time::Duration;
+
+/// A generic clock resource that tracks how much it has advanced since its
+/// previous update and since its creation.
+///
+/// Multiple instances of this resource are used by the engine, with a context type `T`
+/// selecting what the clock measures:
+///
+/// - [`Time<Real>`](crate::Real) tracks real wall-clock time elapsed.
+/// - [`Time<Virtual>`](crate::Virtual) tracks virtual game time that may be paused or scaled.
+/// - [`Time<Fixed>`](crate::Fixed) tracks fixed timesteps based on virtual time.
+/// - [`Time`] is a generic clock that corresponds to "current" or "default" time: virtual
+///   time during a normal frame, fixed time while a fixed step runs.
+///
+/// # Wrapping
+///
+/// In addition to the elapsed time, the clock tracks a wrapping elapsed time, which wraps
+/// around to zero every [`wrap_period`](Time::wrap_period) (one hour by default). This is
+/// useful for shaders and other places where `f32` precision runs out after a long
+/// uptime, which matters for long-lived servers.
+///
+/// # Examples
+///
+/// ```
+/// # use obel_time::Time;
+/// # use core::time::Duration;
+/// let mut time = Time::<()>::default();
+/// time.advance_by(Duration::from_millis(250));
+/// time.advance_by(Duration::from_millis(250));
+/// assert_eq!(time.delta(), Duration::from_millis(250));
+/// assert_eq!(time.elapsed(), Duration::from_millis(500));
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Time<T: Default = ()> {
+    context: T,
+    wrap_period: Duration,
+    delta: Duration,
+    delta_secs: f32,
+    delta_secs_f64: f64,
+    elapsed: Duration,
+    elapsed_secs: f32,
+    elapsed_secs_f64: f64,
+    elapsed_wrapped: Duration,
+    elapsed_secs_wrapped: f32,
+    elapsed_secs_wrapped_f64: f64,
+}
+
+impl<T: Default> Time<T> {
+    const DEFAULT_WRAP_PERIOD: Duration = Duration::from_secs(3600); // 1 hour
+
+    /// Create a new clock from context with [`Self::delta`] and [`Self::elapsed`] starting from
+    /// zero.
+    pub fn new_with(context: T) -> Self {
+        Self {
+            context,
+            ..Default::default()
+        }
+    }
+
+    /// Advance this clock by adding a `delta` duration to it.
+    ///
+    /// The added duration will be returned by [`Self::delta`] and
+    /// [`Self::elapsed`] will be increased by the duration. Adding
+    /// [`Duration::ZERO`] is allowed and will set [`Self::delta`] to zero.
+    pub fn advance_by(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.delta_secs = self.delta.as_secs_f32();
+        self.delta_secs_f64 = self.delta.as_secs_f64();
+        self.elapsed += delta;
+        self.elapsed_secs = self.elapsed.as_secs_f32();
+        self.elapsed_secs_f64 = self.elapsed.as_secs_f64();
+        self.elapsed_wrapped = duration_rem(self.elapsed, self.wrap_period);
+        self.elapsed_secs_wrapped = self.elapsed_wrapped.as_secs_f32();
+        self.elapsed_secs_wrapped_f64 = self.elapsed_wrapped.as_secs_f64();
+    }
+
+    /// Advance this clock to a specific `elapsed` time.
+    ///
+    /// [`Self::delta()`] will return the amount of time the clock was advanced
+    /// and [`Self::elapsed()`] will be the `elapsed` value passed in. Cannot be
+    /// used to move time backwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elapsed` is less than `Self::elapsed()`.
+    pub fn advance_to(&mut self, elapsed: Duration) {
+        assert!(
+            elapsed >= self.elapsed,
+            "tried to move time backwards to an earlier elapsed moment"
+        );
+        self.advance_by(elapsed - self.elapsed);
+    }
+
+    /// Returns the modulus used to calculate [`elapsed_wrapped`](#method.elapsed_wrapped).
+    ///
+    /// **Note:** The default modulus is one hour.
+    #[inline]
+    pub fn wrap_period(&self) -> Duration {
+        self.wrap_period
+    }
+
+    /// Sets the modulus used to calculate [`elapsed_wrapped`](#method.elapsed_wrapped).
+    ///
+    /// **Note:** This will not take effect until the next update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `wrap_period` is a zero-length duration.
+    #[inline]
+    pub fn set_wrap_period(&mut self, wrap_period: Duration) {
+        assert!(!wrap_period.is_zero(), "division by zero");
+        self.wrap_period = wrap_period;
+    }
+
+    /// Returns how much time has advanced since the last
+    /// [`advance_by`](Self::advance_by), as a [`Duration`].
+    #[inline]
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns how much time has advanced since the last
+    /// [`advance_by`](Self::advance_by), as [`f32`] seconds.
+    #[inline]
+    pub fn delta_secs(&self) -> f32 {
+        self.delta_secs
+    }
+
+    /// Returns how much time has advanced since the last
+    /// [`advance_by`](Self::advance_by), as [`f64`] seconds.
+    #[inline]
+    pub fn delta_secs_f64(&self) -> f64 {
+        self.delta_secs_f64
+    }
+
+    /// Returns how much time has advanced since the clock was created, as [`Duration`].
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns how much time has advanced since the clock was created, as [`f32`] seconds.
+    ///
+    /// **Note:** This is a monotonically increasing value. Its precision will degrade over time.
+    /// If you need an `f32` but that precision loss is unacceptable,
+    /// use [`elapsed_secs_wrapped`](#method.elapsed_secs_wrapped).
+    #[inline]
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed_secs
+    }
+
+    /// Returns how much time has advanced since the clock was created, as [`f64`] seconds.
+    #[inline]
+    pub fn elapsed_secs_f64(&self) -> f64 {
+        self.elapsed_secs_f64
+    }
+
+    /// Returns how much time has advanced since the clock was created modulo
+    /// the [`wrap_period`](#method.wrap_period), as [`Duration`].
+    #[inline]
+    pub fn elapsed_wrapped(&self) -> Duration {
+        self.elapsed_wrapped
+    }
+
+    /// Returns how much time has advanced since the clock was created modulo
+    /// the [`wrap_period`](#method.wrap_period), as [`f32`] seconds.
+    ///
+    /// This method is intended for applications (e.g. shaders) that require an [`f32`] value but
+    /// suffer from the gradual precision loss of [`elapsed_secs`](#method.elapsed_secs).
+    #[inline]
+    pub fn elapsed_secs_wrapped(&self) -> f32 {
+        self.elapsed_secs_wrapped
+    }
+
+    /// Returns how much time has advanced since the clock was created modulo
+    /// the [`wrap_period`](#method.wrap_period), as [`f64`] seconds.
+    #[inline]
+    pub fn elapsed_secs_wrapped_f64(&self) -> f64 {
+        self.elapsed_secs_wrapped_f64
+    }
+
+    /// Returns a reference to the context of this specific clock.
+    #[inline]
+    pub fn context(&self) -> &T {
+        &self.context
+    }
+
+    /// Returns a mutable reference to the context of this specific clock.
+    #[inline]
+    pub fn context_mut(&mut self) -> &mut T {
+        &mut self.context
+    }
+
+    /// Returns a copy of this clock as fully generic clock without context.
+    #[inline]
+    pub fn as_generic(&self) -> Time<()> {
+        Time {
+            context: (),
+            wrap_period: self.wrap_period,
+            delta: self.delta,
+            delta_secs: self.delta_secs,
+            delta_secs_f64: self.delta_secs_f64,
+            elapsed: self.elapsed,
+            elapsed_secs: self.elapsed_secs,
+            elapsed_secs_f64: self.elapsed_secs_f64,
+            elapsed_wrapped: self.elapsed_wrapped,
+            elapsed_secs_wrapped: self.elapsed_secs_wrapped,
+            elapsed_secs_wrapped_f64: self.elapsed_secs_wrapped_f64,
+        }
+    }
+}
+
+impl<T: Default> Default for Time<T> {
+    fn default() -> Self {
+        Self {
+            context: Default::default(),
+            wrap_period: Self::DEFAULT_WRAP_PERIOD,
+            delta: Duration::ZERO,
+            delta_secs: 0.0,
+            delta_secs_f64: 0.0,
+            elapsed: Duration::ZERO,
+            elapsed_secs: 0.0,
+            elapsed_secs_f64: 0.0,
+            elapsed_wrapped: Duration::ZERO,
+            elapsed_secs_wrapped: 0.0,
+            elapsed_secs_wrapped_f64: 0.0,
+        }
+    }
+}
+
+/// Returns the remainder of `dividend` divided by `divisor`.
+fn duration_rem(dividend: Duration, divisor: Duration) -> Duration {
+    // `Duration` does not have a built-in modulo operation
+    let quotient = (dividend.as_nanos() / divisor.as_nanos()) as u32;
+    dividend - (quotient * divisor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Time;
+    use core::time::Duration;
+
+    #[test]
+    fn test_initial_state() {
+        let time: Time = Time::default();
+
+        assert_eq!(time.wrap_period(), Time::<()>::DEFAULT_WRAP_PERIOD);
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.delta_secs(), 0.0);
+        assert_eq!(time.delta_secs_f64(), 0.0);
+        assert_eq!(time.elapsed(), Duration::ZERO);
+        assert_eq!(time.elapsed_secs(), 0.0);
+        assert_eq!(time.elapsed_secs_f64(), 0.0);
+        assert_eq!(time.elapsed_wrapped(), Duration::ZERO);
+        assert_eq!(time.elapsed_secs_wrapped(), 0.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 0.0);
+    }
+
+    #[test]
+    fn test_advance_by() {
+        let mut time: Time = Time::default();
+
+        time.advance_by(Duration::from_millis(250));
+
+        assert_eq!(time.delta(), Duration::from_millis(250));
+        assert_eq!(time.delta_secs(), 0.25);
+        assert_eq!(time.delta_secs_f64(), 0.25);
+        assert_eq!(time.elapsed(), Duration::from_millis(250));
+        assert_eq!(time.elapsed_secs(), 0.25);
+        assert_eq!(time.elapsed_secs_f64(), 0.25);
+
+        time.advance_by(Duration::from_millis(500));
+
+        assert_eq!(time.delta(), Duration::from_millis(500));
+        assert_eq!(time.delta_secs(), 0.5);
+        assert_eq!(time.delta_secs_f64(), 0.5);
+        assert_eq!(time.elapsed(), Duration::from_millis(750));
+        assert_eq!(time.elapsed_secs(), 0.75);
+        assert_eq!(time.elapsed_secs_f64(), 0.75);
+
+        time.advance_by(Duration::ZERO);
+
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.delta_secs(), 0.0);
+        assert_eq!(time.delta_secs_f64(), 0.0);
+        assert_eq!(time.elapsed(), Duration::from_millis(750));
+        assert_eq!(time.elapsed_secs(), 0.75);
+        assert_eq!(time.elapsed_secs_f64(), 0.75);
+    }
+
+    #[test]
+    fn test_advance_to() {
+        let mut time: Time = Time::default();
+
+        time.advance_to(Duration::from_millis(250));
+
+        assert_eq!(time.delta(), Duration::from_millis(250));
+        assert_eq!(time.elapsed(), Duration::from_millis(250));
+
+        time.advance_to(Duration::from_millis(750));
+
+        assert_eq!(time.delta(), Duration::from_millis(500));
+        assert_eq!(time.elapsed(), Duration::from_millis(750));
+
+        time.advance_to(Duration::from_millis(750));
+
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.elapsed(), Duration::from_millis(750));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_advance_to_backwards_panics() {
+        let mut time: Time = Time::default();
+
+        time.advance_to(Duration::from_millis(750));
+
+        time.advance_to(Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let mut time: Time = Time::default();
+        time.set_wrap_period(Duration::from_secs(3));
+
+        time.advance_by(Duration::from_secs(2));
+
+        assert_eq!(time.elapsed_wrapped(), Duration::from_secs(2));
+        assert_eq!(time.elapsed_secs_wrapped(), 2.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 2.0);
+
+        time.advance_by(Duration::from_secs(2));
+
+        assert_eq!(time.elapsed_wrapped(), Duration::from_secs(1));
+        assert_eq!(time.elapsed_secs_wrapped(), 1.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 1.0);
+
+        time.advance_by(Duration::from_secs(2));
+
+        assert_eq!(time.elapsed_wrapped(), Duration::ZERO);
+        assert_eq!(time.elapsed_secs_wrapped(), 0.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 0.0);
+
+        time.advance_by(Duration::new(3, 250_000_000));
+
+        assert_eq!(time.elapsed_wrapped(), Duration::from_millis(250));
+        assert_eq!(time.elapsed_secs_wrapped(), 0.25);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 0.25);
+    }
+
+    #[test]
+    fn test_wrapping_change() {
+        let mut time: Time = Time::default();
+        time.set_wrap_period(Duration::from_secs(5));
+
+        time.advance_by(Duration::from_secs(8));
+
+        assert_eq!(time.elapsed_wrapped(), Duration::from_secs(3));
+        assert_eq!(time.elapsed_secs_wrapped(), 3.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 3.0);
+
+        time.set_wrap_period(Duration::from_secs(2));
+
+        assert_eq!(time.elapsed_wrapped(), Duration::from_secs(3));
+        assert_eq!(time.elapsed_secs_wrapped(), 3.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 3.0);
+
+        time.advance_by(Duration::ZERO);
+
+        // Time will wrap to modulo duration from full `elapsed()`, not to what
+        // is left in `elapsed_wrapped()`. This test of values is here to ensure
+        // that we notice if we change that behavior.
+        assert_eq!(time.elapsed_wrapped(), Duration::from_secs(0));
+        assert_eq!(time.elapsed_secs_wrapped(), 0.0);
+        assert_eq!(time.elapsed_secs_wrapped_f64(), 0.0);
+    }
+}
@@ -0,0 +1,143 @@
+//! Loads, reloads and unloads the plugin of `engine/core/app/dynamic_plugin_fixture`.
+//!
+//! Run with `cargo test --features dynamic_linking,dynamic_plugin -- --ignored`.
+
+#![cfg(all(feature = "dynamic_linking", feature = "dynamic_plugin"))]
+#![expect(unsafe_code, reason = "Loading and calling into shared libraries is inherently unsafe.")]
+
+use libloading::Library;
+use obel::app::{App, AppError, DynamicPluginError, Plugin, PluginsState};
+use std::{
+    env::{
+        self,
+        consts::{DLL_PREFIX, DLL_SUFFIX},
+    },
+    path::PathBuf,
+    process::Command,
+};
+
+/// The name of the plugin the fixture plugin depends on.
+struct FixtureDependency;
+
+impl Plugin for FixtureDependency {
+    fn build(&self, _app: &mut App) {}
+
+    fn name(&self) -> &str {
+        "obel_app_dynamic_plugin_fixture::dependency"
+    }
+}
+
+/// Mirrors `FixtureCounts` in the fixture library.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FixtureCounts {
+    builds: u32,
+    finishes: u32,
+    cleanups: u32,
+    drops: u32,
+}
+
+fn counts(builds: u32, finishes: u32, cleanups: u32, drops: u32) -> FixtureCounts {
+    FixtureCounts {
+        builds,
+        finishes,
+        cleanups,
+        drops,
+    }
+}
+
+/// Builds the fixture library with the toolchain running the tests, in its own target
+/// directory so that it does not wait on the lock held by the running build. Its dependencies
+/// get the same hashes as the ones of this test, so it links the `obel_dylib` already loaded.
+fn build_fixture() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    // `target/debug/deps/dynamic_plugin-<hash>`
+    let target_dir = exe.ancestors().nth(3).unwrap().join("dynamic_plugin_fixture");
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "-p", "obel_app_dynamic_plugin_fixture"])
+        .args(["--features", "dynamic_linking", "--target-dir"])
+        .arg(&target_dir)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to build the dynamic plugin fixture");
+    target_dir
+        .join("debug")
+        .join(format!("{DLL_PREFIX}obel_app_dynamic_plugin_fixture{DLL_SUFFIX}"))
+}
+
+#[test]
+#[ignore = "builds the fixture library with cargo"]
+fn load_unload_and_reload_fixture() {
+    let path = build_fixture();
+    // SAFETY: the fixture is built from this repository. Holding this handle keeps the
+    // library mapped, so that its counters outlive the loader's handle.
+    let fixture = unsafe { Library::new(&path) }.unwrap();
+    // SAFETY: the signatures match the fixture's exports.
+    let (fixture_counts, set_ready) = unsafe {
+        (
+            *fixture.get::<extern "C" fn() -> FixtureCounts>(b"fixture_counts\0").unwrap(),
+            *fixture.get::<extern "C" fn(bool)>(b"fixture_set_ready\0").unwrap(),
+        )
+    };
+
+    let mut app = App::new();
+    let mut loader = obel::dynamic_plugin_loader();
+    set_ready(false);
+    // SAFETY: see above, for all loads below.
+    let name = unsafe { loader.load(&mut app, &path) }.unwrap().to_owned();
+    assert_eq!(name, "obel_app_dynamic_plugin_fixture::FixturePlugin");
+    assert!(app.is_plugin_name_added(&name));
+    assert!(loader.is_loaded(&path));
+    assert_eq!(fixture_counts(), counts(1, 0, 0, 0));
+    assert!(matches!(
+        unsafe { loader.load(&mut app, &path) },
+        Err(DynamicPluginError::AlreadyLoaded(_))
+    ));
+
+    // the app waits for the plugin to be ready and checks its dependencies
+    assert_eq!(app.plugins_state(), PluginsState::Adding);
+    set_ready(true);
+    assert_eq!(app.plugins_state(), PluginsState::Ready);
+    assert!(matches!(
+        app.check_plugin_dependencies(),
+        Err(AppError::MissingPluginDependency { plugin_name, .. }) if plugin_name == name
+    ));
+    app.add_plugins(FixtureDependency);
+    app.finish();
+    app.cleanup();
+    assert_eq!(fixture_counts(), counts(1, 1, 1, 0));
+
+    // a plugin loaded into a running app is finished and cleaned up right away when ready
+    unsafe { loader.reload(&mut app, &path) }.unwrap();
+    assert_eq!(fixture_counts(), counts(2, 2, 2, 1));
+    assert!(app.is_plugin_name_added(&name));
+    assert!(!loader.is_pending(&path));
+
+    // ... or by a later frame
+    set_ready(false);
+    unsafe { loader.reload(&mut app, &path) }.unwrap();
+    assert_eq!(fixture_counts(), counts(3, 2, 2, 2));
+    assert!(loader.is_pending(&path));
+    assert_eq!(loader.finish_pending(&mut app), 1);
+    set_ready(true);
+    assert_eq!(loader.finish_pending(&mut app), 0);
+    assert_eq!(fixture_counts(), counts(3, 3, 3, 2));
+    assert!(!loader.is_pending(&path));
+
+    loader.unload(&mut app, &path).unwrap();
+    assert_eq!(fixture_counts(), counts(3, 3, 3, 3));
+    assert!(!app.is_plugin_name_added(&name));
+    assert!(!loader.is_loaded(&path));
+
+    // too late for `App::finish` to report the missing dependency
+    let mut app = App::new();
+    app.finish();
+    app.cleanup();
+    assert!(matches!(
+        unsafe { loader.load(&mut app, &path) },
+        Err(DynamicPluginError::App(AppError::MissingPluginDependency { .. }))
+    ));
+    assert_eq!(fixture_counts(), counts(3, 3, 3, 4));
+    assert!(!loader.is_loaded(&path));
+}